@@ -1,10 +1,11 @@
-use bidding_platform::msg::{InstantiateMsg, ExecMsg, QueryMsg};
+use bidding_platform::msg::{InstantiateMsg, ExecMsg, MigrateMsg, QueryMsg};
 use cosmwasm_schema::write_api;
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         execute: ExecMsg,
-        query: QueryMsg
+        query: QueryMsg,
+        migrate: MigrateMsg
     }
 }
\ No newline at end of file
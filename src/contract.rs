@@ -1,71 +1,290 @@
-use cosmwasm_std::{DepsMut, MessageInfo, Response, StdResult, Uint128};
+use cosmwasm_std::{DepsMut, Response, StdResult};
 use cw2::set_contract_version;
 
 use crate::{
     msg::InstantiateMsg,
-    state::{State, HIGHEST_BID, OWNER, STATE},
+    state::{GUARDIAN, NEXT_AUCTION_ID},
 };
 
-pub fn instantiate(deps: DepsMut, info: MessageInfo, msg: InstantiateMsg) -> StdResult<Response> {
+pub fn instantiate(deps: DepsMut, msg: InstantiateMsg) -> StdResult<Response> {
     set_contract_version(
         deps.storage,
         env!("CARGO_PKG_NAME"),
         env!("CARGO_PKG_VERSION"),
     )?;
-    
-    STATE.save(
-        deps.storage,
-        &State {
-            open: true,
-            part: msg.part,
-            token: msg.token,
-        },
-    )?;
 
-    if let Some(addr) = msg.owner {
-        OWNER.save(deps.storage, &deps.api.addr_validate(&addr)?)?
-    } else {
-        OWNER.save(deps.storage, &info.sender)?;
-    };
+    NEXT_AUCTION_ID.save(deps.storage, &0)?;
+
+    if let Some(guardian) = msg.guardian {
+        let guardian = deps.api.addr_validate(&guardian)?;
+        GUARDIAN.save(deps.storage, &guardian)?;
+    }
 
-    HIGHEST_BID.save(deps.storage, &(info.sender.clone(), Uint128::zero()))?;
-    
     Ok(Response::new())
 }
 
+pub mod migrate;
+
 pub mod query {
-    use cosmwasm_std::{Deps, Order, StdResult};
+    use cosmwasm_std::{Coin, Deps, Env, Order, StdResult};
+    use cw_storage_plus::Bound;
 
     use crate::{
-        msg::{Bid, ValueResponse},
-        state::{BIDS, HIGHEST_BID, OWNER, STATE},
+        msg::{
+            AccruedCommissionResponse, AuctionStatus, Bid, BidHistoryResponse, BidOfResponse,
+            BidsResponse, ConfigResponse, CurrentPriceResponse, HighestBidResponse,
+            PendingOwnerResponse, StatusResponse, ValueResponse,
+        },
+        state::{
+            bid_records, bid_totals, ACCRUED_COMMISSION, ACCRUED_DENOM_COMMISSION, HIGHEST_BID,
+            OWNER, PENDING_OWNER, STATE,
+        },
     };
 
-    pub fn value(deps: Deps) -> StdResult<ValueResponse> {
-        let state = STATE.load(deps.storage)?;
-        let owner = OWNER.load(deps.storage)?;
-        let highest_bid = HIGHEST_BID.load(deps.storage)?;
+    const DEFAULT_LIMIT: u32 = 10;
+    const MAX_LIMIT: u32 = 30;
 
-        let bids = BIDS
-            .range(deps.storage, None, None, Order::Ascending)
-            .map(|item| {
-                let (addr, amount) = item?;
-                Ok(Bid { addr, amount })
-            })
-            .collect::<StdResult<_>>()?;
+    pub fn value(deps: Deps, auction_id: u64) -> StdResult<ValueResponse> {
+        let state = STATE.load(deps.storage, auction_id)?;
+        let owner = OWNER.load(deps.storage, auction_id)?;
+        let highest_bid = HIGHEST_BID.load(deps.storage, auction_id)?;
 
         Ok(ValueResponse {
-            bids: bids,
             highest_bid: Bid {
                 addr: highest_bid.0,
                 amount: highest_bid.1,
             },
-            owner: owner,
-            open: state.open,
+            owner,
+            open: state.status == AuctionStatus::Open,
+            status: state.status,
             part: state.part,
             token: state.token,
+            end_time: state.end_time,
+            end_height: state.end_height,
+            settlement_deadline: state.settlement_deadline,
+            keeper_reward: state.keeper_reward,
+            min_increment: state.min_increment,
+            min_increment_percent: state.min_increment_percent,
+            reserve: state.reserve,
+            buy_now_price: state.buy_now_price,
+            mode: state.mode,
+            settlement: state.settlement,
+            extension_window: state.extension_window,
+            extension_duration: state.extension_duration,
+            max_end_time: state.max_end_time,
+            fee_collector: state.fee_collector,
+            commission_mode: state.commission_mode,
+            refund_commission_on_cancel: state.refund_commission_on_cancel,
+            denom_rates: state.denom_rates,
+            owner_renounced: state.owner_renounced,
+            nft: state.nft,
+        })
+    }
+
+    pub fn config(deps: Deps, auction_id: u64) -> StdResult<ConfigResponse> {
+        let state = STATE.load(deps.storage, auction_id)?;
+        let owner = OWNER.load(deps.storage, auction_id)?;
+
+        Ok(ConfigResponse {
+            owner,
+            owner_renounced: state.owner_renounced,
+            token: state.token,
+            part: state.part,
+        })
+    }
+
+    pub fn pending_owner(deps: Deps, auction_id: u64) -> StdResult<PendingOwnerResponse> {
+        let pending = PENDING_OWNER.may_load(deps.storage, auction_id)?;
+
+        Ok(PendingOwnerResponse {
+            new_owner: pending.as_ref().map(|pending| pending.new_owner.clone()),
+            expiry: pending.and_then(|pending| pending.expiry),
+        })
+    }
+
+    pub fn status(deps: Deps, env: Env, auction_id: u64) -> StdResult<StatusResponse> {
+        let state = STATE.load(deps.storage, auction_id)?;
+
+        Ok(StatusResponse {
+            ended: state.ended(&env.block),
+            status: state.status,
+            end_time: state.end_time,
+            end_height: state.end_height,
+            settlement_deadline: state.settlement_deadline,
+            paused: state.paused,
+            pause_reason: state.pause_reason,
+        })
+    }
+
+    pub fn highest_bid(deps: Deps, auction_id: u64) -> StdResult<HighestBidResponse> {
+        let (addr, amount) = HIGHEST_BID.load(deps.storage, auction_id)?;
+        let bid = (!amount.is_zero()).then_some(Bid { addr, amount });
+
+        Ok(HighestBidResponse { bid })
+    }
+
+    pub fn bid_of(deps: Deps, auction_id: u64, address: String) -> StdResult<BidOfResponse> {
+        let address = deps.api.addr_validate(&address)?;
+        let amount = bid_totals().may_load(deps.storage, (auction_id, &address))?;
+
+        Ok(BidOfResponse { amount })
+    }
+
+    pub fn highest_bid_at_height(
+        deps: Deps,
+        auction_id: u64,
+        height: u64,
+    ) -> StdResult<HighestBidResponse> {
+        // Snapshots hold the state at the start of a block
+        let bid = HIGHEST_BID
+            .may_load_at_height(deps.storage, auction_id, height.saturating_add(1))?
+            .filter(|(_, amount)| !amount.is_zero())
+            .map(|(addr, amount)| Bid { addr, amount });
+
+        Ok(HighestBidResponse { bid })
+    }
+
+    pub fn bid_of_at_height(
+        deps: Deps,
+        auction_id: u64,
+        address: String,
+        height: u64,
+    ) -> StdResult<BidOfResponse> {
+        let address = deps.api.addr_validate(&address)?;
+        let amount = bid_totals().may_load_at_height(
+            deps.storage,
+            (auction_id, &address),
+            height.saturating_add(1),
+        )?;
+
+        Ok(BidOfResponse { amount })
+    }
+
+    pub fn bids(
+        deps: Deps,
+        auction_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<BidsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_after = start_after
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?;
+
+        let bids = bid_totals()
+            .prefix(auction_id)
+            .range(
+                deps.storage,
+                start_after.as_ref().map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .map(|item| {
+                let (addr, amount) = item?;
+                Ok(Bid { addr, amount })
+            })
+            .collect::<StdResult<_>>()?;
+
+        Ok(BidsResponse { bids })
+    }
+
+    pub fn top_bids(
+        deps: Deps,
+        auction_id: u64,
+        start_after: Option<Bid>,
+        limit: Option<u32>,
+    ) -> StdResult<BidsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        // The cursor doesn't depend on the bidder's current total, which may
+        // have changed or be gone since the previous page
+        let end = start_after
+            .map(|bid| -> StdResult<_> {
+                let addr = deps.api.addr_validate(bid.addr.as_str())?;
+                Ok(Bound::exclusive((bid.amount.u128(), (auction_id, addr))))
+            })
+            .transpose()?;
+
+        let bids = bid_totals()
+            .idx
+            .amount
+            .sub_prefix(auction_id)
+            .range(deps.storage, None, end, Order::Descending)
+            .take(limit)
+            .map(|item| {
+                let ((_, addr), amount) = item?;
+                Ok(Bid { addr, amount })
+            })
+            .collect::<StdResult<_>>()?;
+
+        Ok(BidsResponse { bids })
+    }
+
+    pub fn accrued_commission(deps: Deps, auction_id: u64) -> StdResult<AccruedCommissionResponse> {
+        let amount = ACCRUED_COMMISSION
+            .may_load(deps.storage, auction_id)?
+            .unwrap_or_default();
+
+        let denoms = ACCRUED_DENOM_COMMISSION
+            .prefix(auction_id)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| {
+                let (denom, amount) = item?;
+                Ok(Coin { denom, amount })
+            })
+            .collect::<StdResult<_>>()?;
+
+        Ok(AccruedCommissionResponse { amount, denoms })
+    }
+
+    pub fn bid_history(
+        deps: Deps,
+        auction_id: u64,
+        bidder: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<BidHistoryResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        let history = bid_records();
+        let bids = match bidder {
+            Some(bidder) => history
+                .idx
+                .bidder
+                .prefix((auction_id, deps.api.addr_validate(&bidder)?))
+                .range(
+                    deps.storage,
+                    start_after.map(|id| Bound::exclusive((auction_id, id))),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .map(|item| item.map(|(_, bid)| bid))
+                .collect::<StdResult<_>>()?,
+            None => history
+                .prefix(auction_id)
+                .range(
+                    deps.storage,
+                    start_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .map(|item| item.map(|(_, bid)| bid))
+                .collect::<StdResult<_>>()?,
+        };
+
+        Ok(BidHistoryResponse { bids })
+    }
+
+    pub fn current_price(deps: Deps, env: Env, auction_id: u64) -> StdResult<CurrentPriceResponse> {
+        let state = STATE.load(deps.storage, auction_id)?;
+
+        Ok(CurrentPriceResponse {
+            price: state.current_price(env.block.time),
         })
     }
 }
 
-pub mod exec;
\ No newline at end of file
+pub mod exec;
@@ -1,29 +1,400 @@
-use cosmwasm_std::{coins, ensure, BankMsg, DepsMut, MessageInfo, Response, Uint128};
+use cosmwasm_std::{
+    coins, ensure, from_json, to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal,
+    DepsMut, Env, MessageInfo, Response, StdResult, Storage, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, Denom, Expiration};
+use cw721::{Cw721ExecuteMsg, Cw721QueryMsg, Cw721ReceiveMsg, OwnerOfResponse};
 
 use crate::{
     error::ContractError,
-    state::{BIDS, HIGHEST_BID, OWNER, STATE},
+    msg::{
+        bid_commitment, commitment, AuctionMode, AuctionStatus, BidRecord, CommissionMode,
+        CreateAuctionMsg, DenomRate, Nft, PriceDecay, ReceiveMsg, Reserve, Settlement,
+    },
+    state::{
+        bid_records, bid_totals, PendingOwner, SealedBid, State, ACCRUED_COMMISSION,
+        ACCRUED_DENOM_COMMISSION, BID_COMMISSIONS, DENOM_DEPOSITS, GUARDIAN, HIGHEST_BID,
+        NEXT_AUCTION_ID, NEXT_BID_ID, OWNER, PENDING_OWNER, SEALED_BIDS, SECOND_BID, STATE,
+    },
 };
 
-pub fn bid(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
-    let state = STATE.load(deps.storage)?;
-    ensure!(state.open, ContractError::BiddingClosed);
+pub fn create_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: CreateAuctionMsg,
+) -> Result<Response, ContractError> {
+    create(deps, env, info.sender, msg, None)
+}
 
-    let mut bid: Uint128 = Uint128::zero();
-    let mut comission: Uint128 = Uint128::zero();
+pub fn receive_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let seller = deps.api.addr_validate(&msg.sender)?;
 
-    for coin in info.funds.iter() {
-        if coin.denom == state.token {
-            bid = coin.amount;
-            comission = bid * state.part;
-            bid = bid - comission;
+    // Anyone can send the message, so make sure the NFT really is in escrow
+    let resp: OwnerOfResponse = deps.querier.query_wasm_smart(
+        &info.sender,
+        &Cw721QueryMsg::OwnerOf {
+            token_id: msg.token_id.clone(),
+            include_expired: None,
+        },
+    )?;
+    ensure!(
+        resp.owner == env.contract.address.as_str(),
+        ContractError::NftNotEscrowed {
+            token_id: msg.token_id
         }
+    );
+
+    let nft = Nft {
+        contract: info.sender,
+        token_id: msg.token_id,
+        seller: seller.clone(),
+    };
+
+    create(deps, env, seller, from_json(&msg.msg)?, Some(nft))
+}
+
+fn create(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    msg: CreateAuctionMsg,
+    nft: Option<Nft>,
+) -> Result<Response, ContractError> {
+    ensure!(msg.part <= Decimal::one(), ContractError::PartTooHigh);
+
+    let keeper_reward = msg.keeper_reward.unwrap_or_default();
+    ensure!(
+        keeper_reward <= Decimal::one(),
+        ContractError::KeeperRewardTooHigh
+    );
+    if let (Some(end_time), Some(max_end_time)) = (msg.end_time, msg.max_end_time) {
+        ensure!(max_end_time >= end_time, ContractError::MaxEndBeforeEnd);
+    }
+    if let (Some(end_time), Some(deadline)) = (msg.end_time, msg.settlement_deadline) {
+        // Soft close can move the end time up to `max_end_time`
+        let latest_end = match msg.extension_duration.unwrap_or_default() {
+            0 => Some(end_time),
+            _ => msg.max_end_time,
+        };
+        ensure!(
+            latest_end.is_some_and(|end| deadline >= end),
+            ContractError::SettlementBeforeEnd
+        );
+    }
+    if let Some(price) = msg.buy_now_price {
+        let reserve = match msg.reserve {
+            Some(Reserve::Public(reserve)) => reserve,
+            _ => Uint128::zero(),
+        };
+        ensure!(
+            !price.is_zero() && price >= reserve,
+            ContractError::InvalidBuyNowPrice
+        );
     }
 
-    let highest = HIGHEST_BID.load(deps.storage)?.1;
-    let mut sender_total = BIDS
-        .may_load(deps.storage, &info.sender)?
+    let mode = msg.mode.unwrap_or(AuctionMode::English);
+    if let AuctionMode::Dutch {
+        start_price,
+        floor_price,
+        decay,
+    } = &mode
+    {
+        ensure!(
+            start_price >= floor_price,
+            ContractError::InvalidAuctionMode
+        );
+        ensure!(
+            !matches!(decay, PriceDecay::Stepwise { interval: 0, .. }),
+            ContractError::InvalidAuctionMode
+        );
+    }
+    if let AuctionMode::Sealed {
+        commit_end,
+        reveal_end,
+        ..
+    } = &mode
+    {
+        ensure!(commit_end < reveal_end, ContractError::InvalidAuctionMode);
+    }
+
+    let owner = match msg.owner {
+        Some(addr) => deps.api.addr_validate(&addr)?,
+        None => sender.clone(),
+    };
+
+    let fee_collector = msg
+        .fee_collector
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    let token = match msg.token {
+        Denom::Native(denom) => Denom::Native(denom),
+        Denom::Cw20(addr) => Denom::Cw20(deps.api.addr_validate(addr.as_str())?),
+    };
+
+    let state = State {
+        status: AuctionStatus::Open,
+        part: msg.part,
+        token,
+        end_time: msg.end_time,
+        end_height: msg.end_height,
+        allow_early_close: msg.allow_early_close,
+        settlement_deadline: msg.settlement_deadline,
+        keeper_reward,
+        min_increment: msg.min_increment.unwrap_or_default(),
+        min_increment_percent: msg.min_increment_percent.unwrap_or_default(),
+        reserve: msg.reserve,
+        buy_now_price: msg.buy_now_price,
+        mode,
+        start_time: env.block.time,
+        sealed_deposits: Uint128::zero(),
+        settlement: msg.settlement.unwrap_or(Settlement::FirstPrice),
+        extension_window: msg.extension_window.unwrap_or_default(),
+        extension_duration: msg.extension_duration.unwrap_or_default(),
+        max_end_time: msg.max_end_time,
+        fee_collector,
+        owner_renounced: false,
+        paused: false,
+        pause_reason: None,
+        commission_mode: msg.commission_mode.unwrap_or(CommissionMode::PerBid),
+        refund_commission_on_cancel: msg.refund_commission_on_cancel,
+        denom_rates: msg.denom_rates.unwrap_or_default(),
+        nft: nft.clone(),
+    };
+
+    for (idx, rate) in state.denom_rates.iter().enumerate() {
+        let duplicate = state.denom_rates[..idx]
+            .iter()
+            .any(|other| other.denom == rate.denom);
+        ensure!(
+            !duplicate,
+            ContractError::InvalidDenomRate {
+                denom: rate.denom.clone()
+            }
+        );
+        validate_denom_rate(&state, &rate.denom, rate.rate)?;
+    }
+
+    let auction_id = NEXT_AUCTION_ID.load(deps.storage)?;
+    NEXT_AUCTION_ID.save(deps.storage, &(auction_id + 1))?;
+
+    STATE.save(deps.storage, auction_id, &state)?;
+    OWNER.save(deps.storage, auction_id, &owner)?;
+    HIGHEST_BID.save(
+        deps.storage,
+        auction_id,
+        &(sender.clone(), Uint128::zero()),
+        env.block.height,
+    )?;
+
+    let mut resp = Response::new()
+        .add_attribute("action", "create_auction")
+        .add_attribute("sender", sender.as_str())
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("owner", owner.as_str());
+
+    if let Some(nft) = nft {
+        resp = resp
+            .add_attribute("nft_contract", nft.contract.as_str())
+            .add_attribute("token_id", nft.token_id);
+    }
+
+    Ok(resp)
+}
+
+pub fn bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: u64,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage, auction_id)?;
+    let coin = single_coin(&info.funds)?;
+
+    // Other denoms are only taken at a rate set by the owner
+    let denom = match &state.token {
+        Denom::Native(token) if *token == coin.denom => None,
+        _ => {
+            let rate = state
+                .rate(&coin.denom)
+                .ok_or_else(|| ContractError::InvalidToken {
+                    token: coin.denom.clone(),
+                })?;
+            Some(DenomRate {
+                denom: coin.denom.clone(),
+                rate,
+            })
+        }
+    };
+
+    place_bid(deps, env, state, auction_id, info.sender, coin.amount, denom)
+}
+
+pub fn buy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: u64,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage, auction_id)?;
+    let amount = must_pay(&state.token, &info.funds)?;
+
+    buy_at_current_price(deps, env, state, auction_id, info.sender, amount)
+}
+
+/// Amount of the auction token sent along. Anything but a single, non-zero
+/// coin of the auction denom is rejected.
+fn must_pay(token: &Denom, funds: &[Coin]) -> Result<Uint128, ContractError> {
+    let coin = single_coin(funds)?;
+    ensure!(
+        matches!(token, Denom::Native(denom) if *denom == coin.denom),
+        ContractError::InvalidToken {
+            token: coin.denom.clone()
+        }
+    );
+
+    Ok(coin.amount)
+}
+
+fn single_coin(funds: &[Coin]) -> Result<&Coin, ContractError> {
+    let coin = match funds {
+        [] => return Err(ContractError::NoFunds),
+        [coin] => coin,
+        _ => return Err(ContractError::MultipleCoins),
+    };
+    ensure!(!coin.amount.is_zero(), ContractError::ZeroBid);
+
+    Ok(coin)
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let sender = deps.api.addr_validate(&msg.sender)?;
+
+    ensure!(!msg.amount.is_zero(), ContractError::ZeroBid);
+
+    let receive_msg: ReceiveMsg = from_json(&msg.msg)?;
+    let auction_id = match &receive_msg {
+        ReceiveMsg::Bid { auction_id }
+        | ReceiveMsg::Buy { auction_id }
+        | ReceiveMsg::Commit { auction_id, .. } => *auction_id,
+    };
+
+    let state = STATE.load(deps.storage, auction_id)?;
+    ensure!(
+        state.token == Denom::Cw20(info.sender.clone()),
+        ContractError::InvalidToken {
+            token: info.sender.into()
+        }
+    );
+
+    match receive_msg {
+        ReceiveMsg::Bid { .. } => {
+            place_bid(deps, env, state, auction_id, sender, msg.amount, None)
+        }
+        ReceiveMsg::Buy { .. } => {
+            buy_at_current_price(deps, env, state, auction_id, sender, msg.amount)
+        }
+        ReceiveMsg::Commit { commitment, .. } => {
+            place_commitment(deps, env, state, auction_id, sender, commitment, msg.amount)
+        }
+    }
+}
+
+fn place_bid(
+    deps: DepsMut,
+    env: Env,
+    mut state: State,
+    auction_id: u64,
+    sender: Addr,
+    amount: Uint128,
+    denom: Option<DenomRate>,
+) -> Result<Response, ContractError> {
+    ensure!(
+        state.status == AuctionStatus::Open,
+        ContractError::BiddingClosed
+    );
+    ensure!(!state.paused, ContractError::Paused);
+    ensure!(!state.ended(&env.block), ContractError::BiddingEnded);
+    ensure!(
+        state.mode == AuctionMode::English,
+        ContractError::InvalidAuctionMode
+    );
+
+    let mut sender_total = bid_totals()
+        .may_load(deps.storage, (auction_id, &sender))?
         .unwrap_or_default();
+    let owner = OWNER.load(deps.storage, auction_id)?;
+
+    // Only the part of the bid reaching the buy now price is charged, the
+    // rest is refunded whole. Auctions with a buy now price take no other
+    // denoms.
+    if let Some(price) = state
+        .buy_now_price
+        .filter(|price| sender_total + amount >= *price)
+    {
+        ensure!(
+            denom.is_none(),
+            ContractError::InvalidToken {
+                token: denom.map(|denom| denom.denom).unwrap_or_default()
+            }
+        );
+
+        let paid = price - sender_total;
+        let comission = state.bid_commission(paid);
+        let total = price - comission;
+        charge_bid_commission(deps.storage, &state, auction_id, &sender, comission)?;
+        record_history(deps.storage, &env, auction_id, &sender, paid - comission, total)?;
+
+        HIGHEST_BID.save(deps.storage, auction_id, &(sender.clone(), total), env.block.height)?;
+
+        let settlement_comission = state.settlement_commission(total);
+        accrue_commission(deps.storage, auction_id, settlement_comission)?;
+
+        let excess = amount - paid;
+        let mut resp = Response::new()
+            .add_attribute("action", "bid")
+            .add_attribute("sender", sender.as_str())
+            .add_attribute("auction_id", auction_id.to_string())
+            .add_attribute("sender_total", total)
+            .add_attribute("buy_now", price)
+            .add_messages(sell(
+                deps.storage,
+                auction_id,
+                env.block.height,
+                &mut state,
+                &owner,
+                &sender,
+                total - settlement_comission,
+            )?);
+
+        if !excess.is_zero() {
+            resp = resp.add_message(send_tokens(&state.token, &sender, excess)?);
+        }
+
+        return Ok(resp);
+    }
+
+    let comission = state.bid_commission(amount);
+    let deposit = amount - comission;
+
+    // Bids in other denoms count by their rate to the auction token
+    let bid = match &denom {
+        None => deposit,
+        Some(denom) => deposit * denom.rate,
+    };
+
+    let highest = HIGHEST_BID.load(deps.storage, auction_id)?.1;
     ensure!(
         sender_total + bid > highest,
         ContractError::BidLow {
@@ -33,104 +404,989 @@ pub fn bid(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError>
     );
 
     sender_total += bid;
-    BIDS.save(deps.storage, &info.sender.clone(), &sender_total)?;
-    HIGHEST_BID.save(deps.storage, &(info.sender.clone(), sender_total))?;
+    record_history(deps.storage, &env, auction_id, &sender, bid, sender_total)?;
+
+    match &denom {
+        None => charge_bid_commission(deps.storage, &state, auction_id, &sender, comission)?,
+        Some(denom) => {
+            accrue_denom_commission(deps.storage, auction_id, &denom.denom, comission)?
+        }
+    }
 
-    let commision_message = BankMsg::Send {
-        to_address: OWNER.load(deps.storage)?.into(),
-        amount: coins(comission.u128(), state.token),
+    let mut resp = Response::new()
+        .add_attribute("action", "bid")
+        .add_attribute("sender", sender.as_str())
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("sender_total", sender_total);
+
+    let minimum = state.min_bid(highest);
+    ensure!(
+        sender_total >= minimum,
+        ContractError::BidBelowMinimum { minimum }
+    );
+
+    let height = env.block.height;
+    bid_totals().save(deps.storage, (auction_id, &sender), &sender_total, height)?;
+    record_bid(deps.storage, auction_id, height, &sender, sender_total)?;
+
+    if let Some(DenomRate { denom, .. }) = denom {
+        DENOM_DEPOSITS.update(deps.storage, (auction_id, &sender), |paid| -> StdResult<_> {
+            let mut paid = paid.unwrap_or_default();
+            match paid.coins.iter_mut().find(|coin| coin.denom == denom) {
+                Some(coin) => coin.amount += deposit,
+                None => paid.coins.push(Coin {
+                    denom,
+                    amount: deposit,
+                }),
+            }
+            paid.value += bid;
+            Ok(paid)
+        })?;
+    }
+
+    if let Some(end_time) = state.extended_end_time(env.block.time) {
+        state.end_time = Some(end_time);
+        STATE.save(deps.storage, auction_id, &state)?;
+
+        resp = resp.add_attribute("end_time", end_time.to_string());
+    }
+
+    Ok(resp)
+}
+
+/// Updates the highest bid and the runner-up with the new total of `bidder`.
+fn record_bid(
+    storage: &mut dyn Storage,
+    auction_id: u64,
+    height: u64,
+    bidder: &Addr,
+    total: Uint128,
+) -> StdResult<()> {
+    let highest = HIGHEST_BID.load(storage, auction_id)?;
+
+    if total > highest.1 {
+        if highest.0 != *bidder && !highest.1.is_zero() {
+            SECOND_BID.save(storage, auction_id, &highest)?;
+        }
+        HIGHEST_BID.save(storage, auction_id, &(bidder.clone(), total), height)?;
+    } else if total > runner_up(storage, auction_id)? {
+        SECOND_BID.save(storage, auction_id, &(bidder.clone(), total))?;
+    }
+
+    Ok(())
+}
+
+/// Appends an accepted bid to the bid history.
+fn record_history(
+    storage: &mut dyn Storage,
+    env: &Env,
+    auction_id: u64,
+    bidder: &Addr,
+    increment: Uint128,
+    total: Uint128,
+) -> StdResult<()> {
+    let id = NEXT_BID_ID
+        .may_load(storage, auction_id)?
+        .unwrap_or_default();
+    NEXT_BID_ID.save(storage, auction_id, &(id + 1))?;
+
+    bid_records().save(
+        storage,
+        (auction_id, id),
+        &BidRecord {
+            id,
+            auction_id,
+            bidder: bidder.clone(),
+            increment,
+            total,
+            height: env.block.height,
+            time: env.block.time,
+            tx_index: env.transaction.as_ref().map(|tx| tx.index),
+        },
+    )
+}
+
+fn runner_up(storage: &dyn Storage, auction_id: u64) -> StdResult<Uint128> {
+    Ok(SECOND_BID
+        .may_load(storage, auction_id)?
+        .map(|bid| bid.1)
+        .unwrap_or_default())
+}
+
+fn accrue_commission(
+    storage: &mut dyn Storage,
+    auction_id: u64,
+    comission: Uint128,
+) -> StdResult<()> {
+    ACCRUED_COMMISSION.update(storage, auction_id, |accrued| -> StdResult<_> {
+        Ok(accrued.unwrap_or_default() + comission)
+    })?;
+
+    Ok(())
+}
+
+/// Accrues the commission taken from a bid, remembering the share of
+/// `bidder` if it may have to be refunded.
+fn charge_bid_commission(
+    storage: &mut dyn Storage,
+    state: &State,
+    auction_id: u64,
+    bidder: &Addr,
+    comission: Uint128,
+) -> StdResult<()> {
+    accrue_commission(storage, auction_id, comission)?;
+
+    let tracked = state.refund_commission_on_cancel || !state.keeper_reward.is_zero();
+    if tracked && !comission.is_zero() {
+        BID_COMMISSIONS.update(storage, (auction_id, bidder), |paid| -> StdResult<_> {
+            Ok(paid.unwrap_or_default() + comission)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Takes `reward` out of the accrued commission, or as much of it as wasn't
+/// withdrawn yet.
+fn take_keeper_reward(
+    storage: &mut dyn Storage,
+    auction_id: u64,
+    reward: Uint128,
+) -> StdResult<Uint128> {
+    let accrued = ACCRUED_COMMISSION
+        .may_load(storage, auction_id)?
+        .unwrap_or_default();
+    let reward = reward.min(accrued);
+    ACCRUED_COMMISSION.save(storage, auction_id, &(accrued - reward))?;
+
+    Ok(reward)
+}
+
+fn accrue_denom_commission(
+    storage: &mut dyn Storage,
+    auction_id: u64,
+    denom: &str,
+    comission: Uint128,
+) -> StdResult<()> {
+    ACCRUED_DENOM_COMMISSION.update(storage, (auction_id, denom), |accrued| -> StdResult<_> {
+        Ok(accrued.unwrap_or_default() + comission)
+    })?;
+
+    Ok(())
+}
+
+fn buy_at_current_price(
+    deps: DepsMut,
+    env: Env,
+    mut state: State,
+    auction_id: u64,
+    sender: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    ensure!(
+        state.status == AuctionStatus::Open,
+        ContractError::BiddingClosed
+    );
+    ensure!(!state.paused, ContractError::Paused);
+    ensure!(!state.ended(&env.block), ContractError::BiddingEnded);
+
+    let price = state
+        .current_price(env.block.time)
+        .ok_or(ContractError::InvalidAuctionMode)?;
+    ensure!(amount >= price, ContractError::PriceNotMet { price });
+
+    // Bids are recorded net of the commission charged on them, as in
+    // english auctions
+    let comission = state.bid_commission(price);
+    let total = price - comission;
+    let settlement_comission = state.settlement_commission(total);
+    let owner = OWNER.load(deps.storage, auction_id)?;
+    HIGHEST_BID.save(deps.storage, auction_id, &(sender.clone(), total), env.block.height)?;
+    record_history(deps.storage, &env, auction_id, &sender, total, total)?;
+    accrue_commission(deps.storage, auction_id, comission + settlement_comission)?;
+
+    let mut resp = Response::new()
+        .add_attribute("action", "buy")
+        .add_attribute("sender", sender.as_str())
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("price", price)
+        .add_messages(sell(
+            deps.storage,
+            auction_id,
+            env.block.height,
+            &mut state,
+            &owner,
+            &sender,
+            total - settlement_comission,
+        )?);
+
+    let excess = amount - price;
+    if !excess.is_zero() {
+        resp = resp.add_message(send_tokens(&state.token, &sender, excess)?);
+    }
+
+    Ok(resp)
+}
+
+pub fn commit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: u64,
+    commitment: Binary,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage, auction_id)?;
+    // Commitments can be replaced without adding to the deposit
+    let deposit = if info.funds.is_empty() {
+        Uint128::zero()
+    } else {
+        must_pay(&state.token, &info.funds)?
+    };
+
+    place_commitment(deps, env, state, auction_id, info.sender, commitment, deposit)
+}
+
+fn place_commitment(
+    deps: DepsMut,
+    env: Env,
+    mut state: State,
+    auction_id: u64,
+    sender: Addr,
+    commitment: Binary,
+    deposit: Uint128,
+) -> Result<Response, ContractError> {
+    ensure!(
+        state.status == AuctionStatus::Open,
+        ContractError::BiddingClosed
+    );
+    ensure!(!state.paused, ContractError::Paused);
+
+    let AuctionMode::Sealed { commit_end, .. } = state.mode else {
+        return Err(ContractError::InvalidAuctionMode);
     };
+    ensure!(env.block.time < commit_end, ContractError::NotCommitPhase);
+
+    let previous = SEALED_BIDS
+        .may_load(deps.storage, (auction_id, &sender))?
+        .map(|sealed| sealed.deposit)
+        .unwrap_or_default();
+    let sealed = SealedBid {
+        commitment,
+        deposit: previous + deposit,
+    };
+    ensure!(!sealed.deposit.is_zero(), ContractError::NoFunds);
+    SEALED_BIDS.save(deps.storage, (auction_id, &sender), &sealed)?;
+
+    state.sealed_deposits += deposit;
+    STATE.save(deps.storage, auction_id, &state)?;
 
     let resp = Response::new()
-        .add_attribute("action", "bid")
+        .add_attribute("action", "commit")
+        .add_attribute("sender", sender.as_str())
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("deposit", sealed.deposit);
+
+    Ok(resp)
+}
+
+pub fn reveal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: u64,
+    amount: Uint128,
+    salt: String,
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage, auction_id)?;
+    ensure!(
+        state.status == AuctionStatus::Open,
+        ContractError::BiddingClosed
+    );
+
+    let AuctionMode::Sealed {
+        commit_end,
+        reveal_end,
+        ..
+    } = state.mode
+    else {
+        return Err(ContractError::InvalidAuctionMode);
+    };
+    ensure!(
+        env.block.time >= commit_end && env.block.time < reveal_end,
+        ContractError::NotRevealPhase
+    );
+
+    let sealed = SEALED_BIDS
+        .may_load(deps.storage, (auction_id, &info.sender))?
+        .ok_or(ContractError::NoSealedBid)?;
+    ensure!(
+        sealed.commitment == bid_commitment(auction_id, &info.sender, amount, &salt),
+        ContractError::CommitmentMismatch
+    );
+    ensure!(
+        amount <= sealed.deposit,
+        ContractError::DepositTooLow {
+            deposit: sealed.deposit
+        }
+    );
+
+    SEALED_BIDS.remove(deps.storage, (auction_id, &info.sender));
+    state.sealed_deposits -= sealed.deposit;
+    STATE.save(deps.storage, auction_id, &state)?;
+
+    let comission = state.bid_commission(amount);
+    let bid = amount - comission;
+
+    let height = env.block.height;
+    bid_totals().save(deps.storage, (auction_id, &info.sender), &bid, height)?;
+    record_bid(deps.storage, auction_id, height, &info.sender, bid)?;
+    record_history(deps.storage, &env, auction_id, &info.sender, bid, bid)?;
+    charge_bid_commission(deps.storage, &state, auction_id, &info.sender, comission)?;
+
+    let mut resp = Response::new()
+        .add_attribute("action", "reveal")
         .add_attribute("sender", info.sender.as_str())
-        .add_attribute("sender_total", sender_total)
-        .add_message(commision_message);
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("sender_total", bid);
+
+    let excess = sealed.deposit - amount;
+    if !excess.is_zero() {
+        resp = resp.add_message(send_tokens(&state.token, &info.sender, excess)?);
+    }
 
     Ok(resp)
 }
 
-pub fn close(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
-    let mut state = STATE.load(deps.storage)?;
-    ensure!(state.open, ContractError::BiddingAlreadyClosed);
+pub fn close(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: u64,
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage, auction_id)?;
+    ensure!(
+        state.status == AuctionStatus::Open,
+        ContractError::BiddingAlreadyClosed
+    );
+    // Once the settlement deadline passes a pause no longer holds the bids
+    ensure!(
+        !state.paused || state.settleable(&env.block),
+        ContractError::Paused
+    );
 
-    let owner = OWNER.load(deps.storage)?;
+    let owner = OWNER.load(deps.storage, auction_id)?;
+    let keeper = info.sender != owner;
     ensure!(
-        info.sender == owner,
+        !keeper || state.owner_renounced || state.settleable(&env.block),
         ContractError::Unauthorized {
             owner: owner.into()
         }
     );
+    ensure!(
+        state.closable(&env.block, !keeper),
+        ContractError::EarlyCloseErr
+    );
 
-    state.open = false;
-    STATE.save(deps.storage, &state)?;
+    let highest_bid = HIGHEST_BID.load(deps.storage, auction_id)?;
+    let sold = !highest_bid.1.is_zero() && state.reserve_met(highest_bid.1);
 
-    let highest_bid = HIGHEST_BID.load(deps.storage)?;
+    let forfeited = match state.mode {
+        AuctionMode::Sealed {
+            refund_unrevealed: false,
+            ..
+        } => state.sealed_deposits,
+        _ => Uint128::zero(),
+    };
 
-    if !Uint128::is_zero(&highest_bid.1) {
-        let paying_message = BankMsg::Send {
-            to_address: OWNER.load(deps.storage)?.into(),
-            amount: coins(highest_bid.1.u128(), state.token),
+    let mut resp = Response::new()
+        .add_attribute("action", "close")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("sold", sold.to_string());
+
+    if !forfeited.is_zero() {
+        resp = resp
+            .add_attribute("forfeited", forfeited)
+            .add_message(send_tokens(&state.token, &owner, forfeited)?);
+    }
+
+    if sold {
+        let price = state.clearing_price(highest_bid.1, runner_up(deps.storage, auction_id)?);
+
+        // Part of the price deposited in other denoms is paid out as is
+        let paid = DENOM_DEPOSITS
+            .may_load(deps.storage, (auction_id, &highest_bid.0))?
+            .unwrap_or_default();
+        DENOM_DEPOSITS.remove(deps.storage, (auction_id, &highest_bid.0));
+
+        let mut coins = vec![];
+        for mut coin in paid.coins {
+            let comission = state.settlement_commission(coin.amount);
+            accrue_denom_commission(deps.storage, auction_id, &coin.denom, comission)?;
+            coin.amount -= comission;
+            if !coin.amount.is_zero() {
+                coins.push(coin);
+            }
+        }
+        if !coins.is_empty() {
+            resp = resp.add_message(BankMsg::Send {
+                to_address: owner.to_string(),
+                amount: coins,
+            });
+        }
+
+        let price_in_token = price - paid.value;
+        let comission = state.settlement_commission(price_in_token);
+        accrue_commission(deps.storage, auction_id, comission)?;
+        let payout = price_in_token - comission;
+
+        // The keeper is paid from the commission charged on the winning bid
+        let reward = if keeper {
+            let charged = match state.commission_mode {
+                CommissionMode::PerBid => BID_COMMISSIONS
+                    .may_load(deps.storage, (auction_id, &highest_bid.0))?
+                    .unwrap_or_default(),
+                CommissionMode::OnSettlement => comission,
+            };
+            take_keeper_reward(deps.storage, auction_id, charged * state.keeper_reward)?
+        } else {
+            Uint128::zero()
         };
 
-        BIDS.remove(deps.storage, &highest_bid.0);
+        resp = resp
+            .add_attribute("price", price)
+            .add_messages(sell(
+                deps.storage,
+                auction_id,
+                env.block.height,
+                &mut state,
+                &owner,
+                &highest_bid.0,
+                payout,
+            )?);
+
+        let refund = highest_bid.1 - price;
+        if !refund.is_zero() {
+            resp = resp.add_message(send_tokens(&state.token, &highest_bid.0, refund)?);
+        }
 
-        let resp = Response::new()
-            .add_attribute("action", "close")
-            .add_attribute("sender", info.sender.as_str())
-            .add_message(paying_message);
+        if !reward.is_zero() {
+            let reward_message = send_tokens(&state.token, &info.sender, reward)?;
 
-        Ok(resp)
+            resp = resp
+                .add_attribute("keeper_reward", reward)
+                .add_message(reward_message);
+        }
     } else {
-        let resp = Response::new()
-            .add_attribute("action", "close")
-            .add_attribute("sender", info.sender.as_str());
+        state.status = AuctionStatus::Unsold;
+        STATE.save(deps.storage, auction_id, &state)?;
 
-        Ok(resp)
+        if let Some(nft) = &state.nft {
+            resp = resp.add_message(transfer_nft(nft, &nft.seller)?);
+        }
     }
+
+    Ok(resp)
 }
 
-pub fn retract(
+/// Closes the auction as sold to `winner`, paying `payout` to the owner and
+/// handing over the escrowed NFT.
+fn sell(
+    storage: &mut dyn Storage,
+    auction_id: u64,
+    height: u64,
+    state: &mut State,
+    owner: &Addr,
+    winner: &Addr,
+    payout: Uint128,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    state.status = AuctionStatus::Sold;
+    STATE.save(storage, auction_id, state)?;
+    bid_totals().remove(storage, (auction_id, winner), height)?;
+
+    let mut msgs = vec![];
+
+    if !payout.is_zero() {
+        msgs.push(send_tokens(&state.token, owner, payout)?);
+    }
+
+    if let Some(nft) = &state.nft {
+        msgs.push(transfer_nft(nft, winner)?.into());
+    }
+
+    Ok(msgs)
+}
+
+pub fn reveal_reserve(
+    deps: DepsMut,
+    info: MessageInfo,
+    auction_id: u64,
+    amount: Uint128,
+    salt: String,
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage, auction_id)?;
+    ensure!(
+        state.status == AuctionStatus::Open,
+        ContractError::BiddingAlreadyClosed
+    );
+    ensure_owner(deps.storage, &state, auction_id, &info.sender)?;
+
+    match &state.reserve {
+        Some(Reserve::Hidden(hash)) => ensure!(
+            *hash == commitment(amount, &salt),
+            ContractError::ReserveMismatch
+        ),
+        _ => return Err(ContractError::ReserveNotHidden),
+    }
+
+    state.reserve = Some(Reserve::Public(amount));
+    STATE.save(deps.storage, auction_id, &state)?;
+
+    let resp = Response::new()
+        .add_attribute("action", "reveal_reserve")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("reserve", amount);
+
+    Ok(resp)
+}
+
+pub fn withdraw_commission(
     deps: DepsMut,
     info: MessageInfo,
+    auction_id: u64,
+    amount: Option<Uint128>,
     receiver: Option<String>,
+    denom: Option<String>,
 ) -> Result<Response, ContractError> {
-    let state = STATE.load(deps.storage)?;
-    ensure!(!state.open, ContractError::EarlyRetractErr);
+    let state = STATE.load(deps.storage, auction_id)?;
+    let owner = OWNER.load(deps.storage, auction_id)?;
+    ensure!(
+        info.sender == owner || state.fee_collector.as_ref() == Some(&info.sender),
+        ContractError::Unauthorized {
+            owner: owner.into()
+        }
+    );
+    ensure!(
+        !state.refund_commission_on_cancel
+            || matches!(state.status, AuctionStatus::Sold | AuctionStatus::Unsold),
+        ContractError::CommissionLocked
+    );
 
+    let accrued = match &denom {
+        None => ACCRUED_COMMISSION.may_load(deps.storage, auction_id)?,
+        Some(denom) => ACCRUED_DENOM_COMMISSION.may_load(deps.storage, (auction_id, denom))?,
+    }
+    .unwrap_or_default();
+    let amount = amount.unwrap_or(accrued);
+    ensure!(!amount.is_zero(), ContractError::NoCommission);
     ensure!(
-        BIDS.has(deps.storage, &info.sender),
-        ContractError::NoBidsRetractErr
+        amount <= accrued,
+        ContractError::InsufficientCommission { accrued }
     );
 
-    let total = BIDS.load(deps.storage, &info.sender).unwrap();
-    BIDS.remove(deps.storage, &info.sender);
+    let receiver = match receiver {
+        Some(addr) => deps.api.addr_validate(&addr)?,
+        None => info.sender.clone(),
+    };
 
-    if let Some(receiver) = receiver {
-        let transfer_message = BankMsg::Send {
-            to_address: receiver.clone(),
-            amount: coins(total.u128(), state.token),
-        };
+    let transfer_message = match &denom {
+        None => {
+            ACCRUED_COMMISSION.save(deps.storage, auction_id, &(accrued - amount))?;
+            send_tokens(&state.token, &receiver, amount)?
+        }
+        Some(denom) => {
+            ACCRUED_DENOM_COMMISSION.save(deps.storage, (auction_id, denom), &(accrued - amount))?;
+            send_tokens(&Denom::Native(denom.clone()), &receiver, amount)?
+        }
+    };
+
+    let resp = Response::new()
+        .add_attribute("action", "withdraw_commission")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("receiver", receiver.as_str())
+        .add_attribute("amount", amount)
+        .add_message(transfer_message);
 
-        let resp = Response::new()
-            .add_attribute("action", "close")
-            .add_attribute("sender", info.sender.as_str())
-            .add_attribute("receiver", receiver.as_str())
-            .add_message(transfer_message);
+    Ok(resp)
+}
 
-        Ok(resp)
-    } else {
-        let transfer_message = BankMsg::Send {
-            to_address: info.sender.to_string(),
-            amount: coins(total.u128(), state.token),
-        };
+pub fn set_denom_rate(
+    deps: DepsMut,
+    info: MessageInfo,
+    auction_id: u64,
+    denom: String,
+    rate: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage, auction_id)?;
+    ensure!(
+        state.status == AuctionStatus::Open,
+        ContractError::BiddingAlreadyClosed
+    );
+    ensure_owner(deps.storage, &state, auction_id, &info.sender)?;
+
+    state.denom_rates.retain(|rate| rate.denom != denom);
+    if let Some(rate) = rate {
+        validate_denom_rate(&state, &denom, rate)?;
+        state.denom_rates.push(DenomRate {
+            denom: denom.clone(),
+            rate,
+        });
+    }
+    STATE.save(deps.storage, auction_id, &state)?;
+
+    let mut resp = Response::new()
+        .add_attribute("action", "set_denom_rate")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("denom", denom);
+
+    if let Some(rate) = rate {
+        resp = resp.add_attribute("rate", rate.to_string());
+    }
+
+    Ok(resp)
+}
+
+fn validate_denom_rate(state: &State, denom: &str, rate: Decimal) -> Result<(), ContractError> {
+    ensure!(
+        state.supports_denom_rates(),
+        ContractError::DenomRatesUnsupported
+    );
+    ensure!(
+        !rate.is_zero() && state.token != Denom::Native(denom.to_owned()),
+        ContractError::InvalidDenomRate {
+            denom: denom.to_owned()
+        }
+    );
+
+    Ok(())
+}
+
+pub fn cancel(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: u64,
+    reason: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage, auction_id)?;
+    ensure!(
+        state.status == AuctionStatus::Open,
+        ContractError::BiddingAlreadyClosed
+    );
+    ensure_owner(deps.storage, &state, auction_id, &info.sender)?;
+    // A finished auction belongs to its highest bidder
+    ensure!(
+        !state.ended(&env.block) && !state.settleable(&env.block),
+        ContractError::BiddingEnded
+    );
+
+    state.status = AuctionStatus::Cancelled;
+    STATE.save(deps.storage, auction_id, &state)?;
+
+    let mut resp = Response::new()
+        .add_attribute("action", "cancel")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("auction_id", auction_id.to_string());
+
+    if let Some(reason) = reason {
+        resp = resp.add_attribute("reason", reason);
+    }
+
+    if let Some(nft) = &state.nft {
+        resp = resp.add_message(transfer_nft(nft, &nft.seller)?);
+    }
+
+    Ok(resp)
+}
+
+pub fn transfer_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: u64,
+    new_owner: String,
+    expiry: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage, auction_id)?;
+    ensure_owner(deps.storage, &state, auction_id, &info.sender)?;
+    ensure!(
+        !expiry.is_some_and(|expiry| expiry.is_expired(&env.block)),
+        ContractError::InvalidExpiry
+    );
+
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    PENDING_OWNER.save(
+        deps.storage,
+        auction_id,
+        &PendingOwner {
+            new_owner: new_owner.clone(),
+            expiry,
+        },
+    )?;
+
+    let resp = Response::new()
+        .add_attribute("action", "transfer_ownership")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("new_owner", new_owner.as_str());
+
+    Ok(resp)
+}
+
+pub fn accept_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: u64,
+) -> Result<Response, ContractError> {
+    let pending = PENDING_OWNER
+        .may_load(deps.storage, auction_id)?
+        .filter(|pending| pending.new_owner == info.sender)
+        .ok_or_else(|| ContractError::NotPendingOwner {
+            sender: info.sender.to_string(),
+        })?;
+    ensure!(
+        !pending.expiry.is_some_and(|expiry| expiry.is_expired(&env.block)),
+        ContractError::TransferExpired
+    );
+
+    PENDING_OWNER.remove(deps.storage, auction_id);
+    OWNER.save(deps.storage, auction_id, &info.sender)?;
+
+    let resp = Response::new()
+        .add_attribute("action", "accept_ownership")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("auction_id", auction_id.to_string());
+
+    Ok(resp)
+}
+
+pub fn renounce_ownership(
+    deps: DepsMut,
+    info: MessageInfo,
+    auction_id: u64,
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage, auction_id)?;
+    ensure_owner(deps.storage, &state, auction_id, &info.sender)?;
+    // Without a guardian nobody could unpause the auction anymore
+    ensure!(!state.paused, ContractError::Paused);
+
+    // Without a deadline nobody but the owner could ever close the auction
+    ensure!(
+        state.status != AuctionStatus::Open || state.has_deadline(),
+        ContractError::RenounceWithoutDeadline
+    );
+
+    state.owner_renounced = true;
+    STATE.save(deps.storage, auction_id, &state)?;
+    PENDING_OWNER.remove(deps.storage, auction_id);
+
+    let resp = Response::new()
+        .add_attribute("action", "renounce_ownership")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("auction_id", auction_id.to_string());
+
+    Ok(resp)
+}
+
+pub fn pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    auction_id: u64,
+    reason: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage, auction_id)?;
+    ensure!(
+        state.status == AuctionStatus::Open,
+        ContractError::BiddingAlreadyClosed
+    );
+    ensure_owner_or_guardian(deps.storage, &state, auction_id, &info.sender)?;
+
+    state.paused = true;
+    state.pause_reason = reason.clone();
+    STATE.save(deps.storage, auction_id, &state)?;
+
+    let mut resp = Response::new()
+        .add_attribute("action", "pause")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("auction_id", auction_id.to_string());
+
+    if let Some(reason) = reason {
+        resp = resp.add_attribute("reason", reason);
+    }
+
+    Ok(resp)
+}
+
+pub fn unpause(
+    deps: DepsMut,
+    info: MessageInfo,
+    auction_id: u64,
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage, auction_id)?;
+    ensure!(state.paused, ContractError::NotPaused);
+    ensure_owner_or_guardian(deps.storage, &state, auction_id, &info.sender)?;
+
+    state.paused = false;
+    state.pause_reason = None;
+    STATE.save(deps.storage, auction_id, &state)?;
+
+    let resp = Response::new()
+        .add_attribute("action", "unpause")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("auction_id", auction_id.to_string());
 
-        let resp = Response::new()
-            .add_attribute("action", "close")
-            .add_attribute("sender", info.sender.as_str())
-            .add_attribute("receiver", info.sender.as_str())
-            .add_message(transfer_message);
+    Ok(resp)
+}
+
+/// The guardian can step in even on auctions whose owner renounced.
+fn ensure_owner_or_guardian(
+    storage: &dyn Storage,
+    state: &State,
+    auction_id: u64,
+    sender: &Addr,
+) -> Result<(), ContractError> {
+    if GUARDIAN.may_load(storage)?.as_ref() == Some(sender) {
+        return Ok(());
+    }
+
+    ensure_owner(storage, state, auction_id, sender)
+}
+
+/// Fails unless `sender` holds the owner privileges of the auction.
+fn ensure_owner(
+    storage: &dyn Storage,
+    state: &State,
+    auction_id: u64,
+    sender: &Addr,
+) -> Result<(), ContractError> {
+    ensure!(!state.owner_renounced, ContractError::OwnershipRenounced);
+
+    let owner = OWNER.load(storage, auction_id)?;
+    ensure!(
+        *sender == owner,
+        ContractError::Unauthorized {
+            owner: owner.into()
+        }
+    );
+
+    Ok(())
+}
+
+fn send_tokens(
+    token: &Denom,
+    recipient: impl Into<String>,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    let msg = match token {
+        Denom::Native(denom) => BankMsg::Send {
+            to_address: recipient.into(),
+            amount: coins(amount.u128(), denom),
+        }
+        .into(),
+        Denom::Cw20(contract) => WasmMsg::Execute {
+            contract_addr: contract.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.into(),
+                amount,
+            })?,
+            funds: vec![],
+        }
+        .into(),
+    };
+
+    Ok(msg)
+}
+
+fn transfer_nft(nft: &Nft, recipient: &Addr) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: nft.contract.to_string(),
+        msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+            recipient: recipient.to_string(),
+            token_id: nft.token_id.clone(),
+        })?,
+        funds: vec![],
+    })
+}
+
+pub fn retract(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: u64,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage, auction_id)?;
+    ensure!(
+        state.status != AuctionStatus::Open,
+        ContractError::EarlyRetractErr
+    );
+
+    let paid = DENOM_DEPOSITS
+        .may_load(deps.storage, (auction_id, &info.sender))?
+        .unwrap_or_default();
+    DENOM_DEPOSITS.remove(deps.storage, (auction_id, &info.sender));
+
+    let mut total = bid_totals()
+        .may_load(deps.storage, (auction_id, &info.sender))?
+        .unwrap_or_default()
+        - paid.value;
+    bid_totals().remove(deps.storage, (auction_id, &info.sender), env.block.height)?;
 
-        Ok(resp)
+    let cancelled = state.status == AuctionStatus::Cancelled;
+
+    let refund_unrevealed = match state.mode {
+        AuctionMode::Sealed {
+            refund_unrevealed, ..
+        } => refund_unrevealed || cancelled,
+        _ => false,
+    };
+
+    if refund_unrevealed {
+        if let Some(sealed) = SEALED_BIDS.may_load(deps.storage, (auction_id, &info.sender))? {
+            SEALED_BIDS.remove(deps.storage, (auction_id, &info.sender));
+            state.sealed_deposits -= sealed.deposit;
+            STATE.save(deps.storage, auction_id, &state)?;
+            total += sealed.deposit;
+        }
+    }
+
+    if cancelled && state.refund_commission_on_cancel {
+        if let Some(paid) = BID_COMMISSIONS.may_load(deps.storage, (auction_id, &info.sender))? {
+            BID_COMMISSIONS.remove(deps.storage, (auction_id, &info.sender));
+            ACCRUED_COMMISSION.update(deps.storage, auction_id, |accrued| -> StdResult<_> {
+                Ok(accrued.unwrap_or_default() - paid)
+            })?;
+            total += paid;
+        }
     }
+
+    ensure!(
+        !total.is_zero() || !paid.coins.is_empty(),
+        ContractError::NoBidsRetractErr
+    );
+
+    let receiver = receiver.unwrap_or_else(|| info.sender.to_string());
+
+    let mut resp = Response::new()
+        .add_attribute("action", "close")
+        .add_attribute("sender", info.sender.as_str())
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("receiver", receiver.as_str());
+
+    if !total.is_zero() {
+        resp = resp.add_message(send_tokens(&state.token, &receiver, total)?);
+    }
+
+    if !paid.coins.is_empty() {
+        resp = resp.add_message(BankMsg::Send {
+            to_address: receiver,
+            amount: paid.coins,
+        });
+    }
+
+    Ok(resp)
 }
@@ -0,0 +1,127 @@
+use cosmwasm_std::{ensure, Addr, Decimal, DepsMut, Env, Order, Response, StdResult, Uint128};
+use cw2::{get_contract_version, set_contract_version};
+use cw20::Denom;
+use cw_storage_plus::{Item, Map};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ContractError,
+    msg::{AuctionMode, AuctionStatus, CommissionMode, Settlement},
+    state::{bid_totals, State, HIGHEST_BID, NEXT_AUCTION_ID, OWNER, STATE},
+};
+
+/// Storage layout of 0.1.0, where the contract held a single auction.
+pub mod v0_1 {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    pub struct State {
+        pub open: bool,
+        pub token: String,
+        pub part: Decimal,
+    }
+
+    pub const STATE: Item<State> = Item::new("state");
+    pub const OWNER: Item<Addr> = Item::new("owner");
+    pub const BIDS: Map<&Addr, Uint128> = Map::new("bids");
+    pub const HIGHEST_BID: Item<(Addr, Uint128)> = Item::new("highest_bid");
+}
+
+pub fn migrate(mut deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    ensure!(
+        stored.contract == env!("CARGO_PKG_NAME"),
+        ContractError::InvalidContractName {
+            name: stored.contract
+        }
+    );
+
+    let stored_version: Version = stored.version.parse()?;
+    let version: Version = env!("CARGO_PKG_VERSION").parse()?;
+    ensure!(
+        stored_version <= version,
+        ContractError::Downgrade {
+            stored: stored.version,
+            version: version.to_string(),
+        }
+    );
+
+    if stored_version < Version::new(0, 2, 0) {
+        migrate_v0_1(deps.branch(), &env)?;
+    }
+
+    set_contract_version(deps.storage, env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))?;
+
+    let resp = Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", stored_version.to_string())
+        .add_attribute("to_version", version.to_string());
+
+    Ok(resp)
+}
+
+/// Moves the single 0.1.0 auction under auction id 0.
+fn migrate_v0_1(deps: DepsMut, env: &Env) -> StdResult<()> {
+    let legacy = v0_1::STATE.load(deps.storage)?;
+    let owner = v0_1::OWNER.load(deps.storage)?;
+    let highest_bid = v0_1::HIGHEST_BID.load(deps.storage)?;
+
+    let status = if legacy.open {
+        AuctionStatus::Open
+    } else if highest_bid.1.is_zero() {
+        AuctionStatus::Unsold
+    } else {
+        AuctionStatus::Sold
+    };
+
+    let state = State {
+        status,
+        token: Denom::Native(legacy.token),
+        part: legacy.part,
+        end_time: None,
+        end_height: None,
+        allow_early_close: false,
+        settlement_deadline: None,
+        keeper_reward: Decimal::zero(),
+        min_increment: Uint128::zero(),
+        min_increment_percent: Decimal::zero(),
+        reserve: None,
+        buy_now_price: None,
+        mode: AuctionMode::English,
+        start_time: env.block.time,
+        sealed_deposits: Uint128::zero(),
+        settlement: Settlement::FirstPrice,
+        extension_window: 0,
+        extension_duration: 0,
+        max_end_time: None,
+        fee_collector: None,
+        commission_mode: CommissionMode::PerBid,
+        refund_commission_on_cancel: false,
+        denom_rates: vec![],
+        owner_renounced: false,
+        paused: false,
+        pause_reason: None,
+        nft: None,
+    };
+
+    let bids: Vec<_> = v0_1::BIDS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+
+    for (addr, amount) in bids {
+        v0_1::BIDS.remove(deps.storage, &addr);
+        bid_totals().save(deps.storage, (0, &addr), &amount, env.block.height)?;
+    }
+
+    STATE.save(deps.storage, 0, &state)?;
+    OWNER.save(deps.storage, 0, &owner)?;
+    HIGHEST_BID.save(deps.storage, 0, &highest_bid, env.block.height)?;
+    NEXT_AUCTION_ID.save(deps.storage, &1)?;
+
+    v0_1::STATE.remove(deps.storage);
+    v0_1::OWNER.remove(deps.storage);
+    v0_1::HIGHEST_BID.remove(deps.storage);
+
+    Ok(())
+}
@@ -6,21 +6,138 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Semver(String),
+
+    #[error("Can't migrate from contract {name}")]
+    InvalidContractName { name: String },
+
+    #[error("Can't migrate from version {stored} down to {version}")]
+    Downgrade { stored: String, version: String },
+
+    #[error("Commission can't exceed the whole bid")]
+    PartTooHigh,
+
+    #[error("Owner renounced the auction")]
+    OwnershipRenounced,
+
+    #[error("Auction is paused")]
+    Paused,
+
+    #[error("Auction is not paused")]
+    NotPaused,
+
+    #[error("No ownership transfer pending for {sender}")]
+    NotPendingOwner { sender: String },
+
+    #[error("Ownership transfer expired")]
+    TransferExpired,
+
+    #[error("Expiration is already reached")]
+    InvalidExpiry,
+
+    #[error("Can't renounce an open auction without a deadline")]
+    RenounceWithoutDeadline,
+
+    #[error("Only auctions paying out whole bids in a native token accept other denoms")]
+    DenomRatesUnsupported,
+
+    #[error("Invalid rate for {denom}")]
+    InvalidDenomRate { denom: String },
+
+    #[error("Keeper reward can't exceed the whole commission")]
+    KeeperRewardTooHigh,
+
+    #[error("Contract doesn't hold the NFT {token_id}")]
+    NftNotEscrowed { token_id: String },
+
+    #[error("Buy now price can't be zero or below the reserve")]
+    InvalidBuyNowPrice,
+
+    #[error("Settlement deadline can't be before the latest end time")]
+    SettlementBeforeEnd,
+
+    #[error("Max end time can't be before the end time")]
+    MaxEndBeforeEnd,
+
     #[error("Unauthorized - only {owner} can call it")]
     Unauthorized { owner: String },
 
+    #[error("Auction doesn't accept {token}")]
+    InvalidToken { token: String },
+
+    #[error("No funds sent")]
+    NoFunds,
+
+    #[error("Only a single coin can be sent")]
+    MultipleCoins,
+
+    #[error("Bid amount can't be zero")]
+    ZeroBid,
+
     #[error("Bidding is closed")]
     BiddingClosed,
 
     #[error("Bid low. Highest bid: {highest}, sender total: {sender_total}")]
     BidLow {highest: Uint128, sender_total: Uint128},
 
+    #[error("Bidding has ended")]
+    BiddingEnded,
+
+    #[error("Bid below minimum. Required total: {minimum}")]
+    BidBelowMinimum { minimum: Uint128 },
+
+    #[error("Price not met. Current price: {price}")]
+    PriceNotMet { price: Uint128 },
+
+    #[error("Not supported by this type of auction")]
+    InvalidAuctionMode,
+
+    #[error("Commit phase is over")]
+    NotCommitPhase,
+
+    #[error("Bids can only be revealed during the reveal phase")]
+    NotRevealPhase,
+
+    #[error("No sealed bid to reveal")]
+    NoSealedBid,
+
+    #[error("Revealed bid doesn't match the commitment")]
+    CommitmentMismatch,
+
+    #[error("Revealed bid exceeds the deposit of {deposit}")]
+    DepositTooLow { deposit: Uint128 },
+
+    #[error("No commission to withdraw")]
+    NoCommission,
+
+    #[error("Only {accrued} of commission accrued")]
+    InsufficientCommission { accrued: Uint128 },
+
+    #[error("Commission is refunded if the auction is cancelled")]
+    CommissionLocked,
+
     #[error("Bidding is already closed")]
     BiddingAlreadyClosed,
 
+    #[error("Cant close until bidding has ended")]
+    EarlyCloseErr,
+
     #[error("Cant retract until bidding is closed")]
     EarlyRetractErr,
 
     #[error("Dont have any bids")]
     NoBidsRetractErr,
+
+    #[error("Auction has no hidden reserve")]
+    ReserveNotHidden,
+
+    #[error("Revealed reserve doesn't match the commitment")]
+    ReserveMismatch,
+}
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::Semver(err.to_string())
+    }
 }
\ No newline at end of file
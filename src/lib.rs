@@ -1,8 +1,8 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{DepsMut, Env, MessageInfo, StdResult, Response, Deps, Binary, to_binary};
+use cosmwasm_std::{DepsMut, Env, MessageInfo, StdResult, Response, Deps, Binary, to_json_binary};
 use error::ContractError;
-use msg::InstantiateMsg;
+use msg::{InstantiateMsg, MigrateMsg};
 
 
 mod contract;
@@ -16,33 +16,136 @@ pub mod multitest;
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    info: MessageInfo,
+    _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
-    contract::instantiate(deps, info, msg)
+    contract::instantiate(deps, msg)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: msg::QueryMsg) -> StdResult<Binary> {
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    contract::migrate::migrate(deps, env)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: msg::QueryMsg) -> StdResult<Binary> {
     use msg::QueryMsg::*;
 
     match msg {
-        Value {} => to_binary(&contract::query::value(deps)?),
+        Value { auction_id } => to_json_binary(&contract::query::value(deps, auction_id)?),
+        Config { auction_id } => to_json_binary(&contract::query::config(deps, auction_id)?),
+        PendingOwner { auction_id } => {
+            to_json_binary(&contract::query::pending_owner(deps, auction_id)?)
+        }
+        Status { auction_id } => to_json_binary(&contract::query::status(deps, env, auction_id)?),
+        HighestBid { auction_id } => {
+            to_json_binary(&contract::query::highest_bid(deps, auction_id)?)
+        }
+        BidOf {
+            auction_id,
+            address,
+        } => to_json_binary(&contract::query::bid_of(deps, auction_id, address)?),
+        HighestBidAtHeight { auction_id, height } => to_json_binary(
+            &contract::query::highest_bid_at_height(deps, auction_id, height)?,
+        ),
+        BidOfAtHeight {
+            auction_id,
+            address,
+            height,
+        } => to_json_binary(&contract::query::bid_of_at_height(
+            deps, auction_id, address, height,
+        )?),
+        AccruedCommission { auction_id } => {
+            to_json_binary(&contract::query::accrued_commission(deps, auction_id)?)
+        }
+        BidHistory {
+            auction_id,
+            bidder,
+            start_after,
+            limit,
+        } => to_json_binary(&contract::query::bid_history(
+            deps,
+            auction_id,
+            bidder,
+            start_after,
+            limit,
+        )?),
+        CurrentPrice { auction_id } => {
+            to_json_binary(&contract::query::current_price(deps, env, auction_id)?)
+        }
+        Bids {
+            auction_id,
+            start_after,
+            limit,
+        } => to_json_binary(&contract::query::bids(deps, auction_id, start_after, limit)?),
+        TopBids {
+            auction_id,
+            start_after,
+            limit,
+        } => to_json_binary(&contract::query::top_bids(deps, auction_id, start_after, limit)?),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: msg::ExecMsg,
 ) -> Result<Response, ContractError> {
     use msg::ExecMsg::*;
 
     match msg {
-        Bid {} => contract::exec::bid(deps, info),
-        Close {} => contract::exec::close(deps, info),
-        Retract { receiver } => contract::exec::retract(deps, info, receiver),
+        CreateAuction(msg) => contract::exec::create_auction(deps, env, info, *msg),
+        ReceiveNft(msg) => contract::exec::receive_nft(deps, env, info, msg),
+        Receive(msg) => contract::exec::receive_cw20(deps, env, info, msg),
+        Bid { auction_id } => contract::exec::bid(deps, env, info, auction_id),
+        Buy { auction_id } => contract::exec::buy(deps, env, info, auction_id),
+        Commit {
+            auction_id,
+            commitment,
+        } => contract::exec::commit(deps, env, info, auction_id, commitment),
+        Reveal {
+            auction_id,
+            amount,
+            salt,
+        } => contract::exec::reveal(deps, env, info, auction_id, amount, salt),
+        Close { auction_id } => contract::exec::close(deps, env, info, auction_id),
+        Retract {
+            auction_id,
+            receiver,
+        } => contract::exec::retract(deps, env, info, auction_id, receiver),
+        WithdrawCommission {
+            auction_id,
+            amount,
+            receiver,
+            denom,
+        } => contract::exec::withdraw_commission(deps, info, auction_id, amount, receiver, denom),
+        SetDenomRate {
+            auction_id,
+            denom,
+            rate,
+        } => contract::exec::set_denom_rate(deps, info, auction_id, denom, rate),
+        RevealReserve {
+            auction_id,
+            amount,
+            salt,
+        } => contract::exec::reveal_reserve(deps, info, auction_id, amount, salt),
+        Cancel { auction_id, reason } => {
+            contract::exec::cancel(deps, env, info, auction_id, reason)
+        }
+        TransferOwnership {
+            auction_id,
+            new_owner,
+            expiry,
+        } => contract::exec::transfer_ownership(deps, env, info, auction_id, new_owner, expiry),
+        AcceptOwnership { auction_id } => {
+            contract::exec::accept_ownership(deps, env, info, auction_id)
+        }
+        RenounceOwnership { auction_id } => {
+            contract::exec::renounce_ownership(deps, info, auction_id)
+        }
+        Pause { auction_id, reason } => contract::exec::pause(deps, info, auction_id, reason),
+        Unpause { auction_id } => contract::exec::unpause(deps, info, auction_id),
     }
 }
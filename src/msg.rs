@@ -1,41 +1,434 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Decimal, Addr, Uint128};
+use cosmwasm_std::{Decimal, Addr, Binary, Coin, Timestamp, Uint128};
+use cw20::{Cw20ReceiveMsg, Denom, Expiration};
+use cw721::Cw721ReceiveMsg;
+use sha2::{Digest, Sha256};
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
     #[returns(ValueResponse)]
-    Value {},
+    Value { auction_id: u64 },
+    #[returns(ConfigResponse)]
+    Config { auction_id: u64 },
+    #[returns(PendingOwnerResponse)]
+    PendingOwner { auction_id: u64 },
+    #[returns(StatusResponse)]
+    Status { auction_id: u64 },
+    #[returns(HighestBidResponse)]
+    HighestBid { auction_id: u64 },
+    #[returns(BidOfResponse)]
+    BidOf { auction_id: u64, address: String },
+    /// Highest bid once block `height` was executed, its transactions
+    /// included.
+    #[returns(HighestBidResponse)]
+    HighestBidAtHeight { auction_id: u64, height: u64 },
+    /// Total of `address` once block `height` was executed.
+    #[returns(BidOfResponse)]
+    BidOfAtHeight {
+        auction_id: u64,
+        address: String,
+        height: u64,
+    },
+    #[returns(AccruedCommissionResponse)]
+    AccruedCommission { auction_id: u64 },
+    /// Accepted bids in the order they were placed, optionally only those of
+    /// `bidder`.
+    #[returns(BidHistoryResponse)]
+    BidHistory {
+        auction_id: u64,
+        bidder: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(CurrentPriceResponse)]
+    CurrentPrice { auction_id: u64 },
+    /// Bids ordered by bidder address.
+    #[returns(BidsResponse)]
+    Bids {
+        auction_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Bids ordered from the highest total down. Bidders with equal totals
+    /// are ordered by address, descending. Pages continue after the last bid
+    /// of the previous one.
+    #[returns(BidsResponse)]
+    TopBids {
+        auction_id: u64,
+        start_after: Option<Bid>,
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
 pub struct InstantiateMsg {
+    /// Allowed to pause and unpause any auction besides its owner.
+    pub guardian: Option<String>,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
+
+#[cw_serde]
+pub struct CreateAuctionMsg {
     pub owner: Option<String>,
     pub part: Decimal,
-    pub token: String
+    /// Native denom or cw20 contract bids are placed in.
+    pub token: Denom,
+    /// Bids are rejected once the block time reaches `end_time`.
+    pub end_time: Option<Timestamp>,
+    /// Bids are rejected once the block height reaches `end_height`.
+    pub end_height: Option<u64>,
+    /// Lets the owner close the auction before the deadline is reached.
+    #[serde(default)]
+    pub allow_early_close: bool,
+    /// After `settlement_deadline` anyone can close the auction. It can't be
+    /// before `end_time`, or before `max_end_time` if bids extend the end.
+    pub settlement_deadline: Option<Timestamp>,
+    /// Share of the commission on the highest bid paid to whoever closes the
+    /// auction after the settlement deadline, if it is not the owner.
+    pub keeper_reward: Option<Decimal>,
+    /// Fixed amount a new bid has to exceed the highest bid by.
+    pub min_increment: Option<Uint128>,
+    /// Share of the highest bid a new bid has to exceed it by. When both
+    /// increments are set the larger one applies.
+    pub min_increment_percent: Option<Decimal>,
+    /// Lowest highest bid the item is sold for.
+    pub reserve: Option<Reserve>,
+    /// Bid total that immediately wins the auction, not below a public
+    /// reserve. Anything above it is refunded without commission.
+    pub buy_now_price: Option<Uint128>,
+    /// Defaults to an english auction.
+    pub mode: Option<AuctionMode>,
+    /// Defaults to the winner paying their own bid.
+    pub settlement: Option<Settlement>,
+    /// Bids placed within `extension_window` seconds of `end_time` move it to
+    /// `extension_duration` seconds after the bid.
+    pub extension_window: Option<u64>,
+    pub extension_duration: Option<u64>,
+    /// End time is never extended past `max_end_time`.
+    pub max_end_time: Option<Timestamp>,
+    /// Address allowed to withdraw the commission besides the owner.
+    pub fee_collector: Option<String>,
+    /// Defaults to charging the commission on every bid.
+    pub commission_mode: Option<CommissionMode>,
+    /// Bidders get their commission back when the auction is cancelled. The
+    /// commission can't be withdrawn before the auction is closed then.
+    #[serde(default)]
+    pub refund_commission_on_cancel: bool,
+    /// Native denoms accepted besides `token`. Deposits in them are paid out
+    /// whole, so only english first price auctions in a native token without
+    /// buy now price, keeper reward or commission refunds support them.
+    pub denom_rates: Option<Vec<DenomRate>>,
 }
 
 #[cw_serde]
 pub enum ExecMsg {
-    Bid {},
-    Close {},
+    CreateAuction(Box<CreateAuctionMsg>),
+    /// Starts an auction for the received NFT. The embedded message is a
+    /// `CreateAuctionMsg`.
+    ReceiveNft(Cw721ReceiveMsg),
+    /// Places a bid in cw20 tokens. The embedded message is a `ReceiveMsg`.
+    Receive(Cw20ReceiveMsg),
+    Bid {
+        auction_id: u64
+    },
+    /// Buys the item of a dutch auction at its current price.
+    Buy {
+        auction_id: u64
+    },
+    /// Places a sealed bid. Funds sent along are the deposit the revealed
+    /// bid is paid from.
+    Commit {
+        auction_id: u64,
+        commitment: Binary
+    },
+    /// Opens a sealed bid committed to with
+    /// `bid_commitment(auction_id, sender, amount, salt)`.
+    Reveal {
+        auction_id: u64,
+        amount: Uint128,
+        salt: String
+    },
+    Close {
+        auction_id: u64
+    },
     Retract {
+        auction_id: u64,
         receiver: Option<String>
-    }
+    },
+    /// Withdraws `amount` of the accrued commission, everything if not set.
+    /// Callable by the owner and the fee collector.
+    WithdrawCommission {
+        auction_id: u64,
+        amount: Option<Uint128>,
+        receiver: Option<String>,
+        /// One of the additionally accepted denoms, `token` if not set.
+        denom: Option<String>,
+    },
+    /// Sets the rate bids in `denom` are accepted at, or stops accepting
+    /// them if `rate` is not set. Callable by the owner, on auctions meeting
+    /// the limits of `CreateAuctionMsg::denom_rates`.
+    SetDenomRate {
+        auction_id: u64,
+        denom: String,
+        rate: Option<Decimal>,
+    },
+    /// Discloses a hidden reserve so the auction can close as sold.
+    RevealReserve {
+        auction_id: u64,
+        amount: Uint128,
+        salt: String
+    },
+    /// Aborts an open auction before it ends. Callable by the owner.
+    Cancel {
+        auction_id: u64,
+        reason: Option<String>,
+    },
+    /// Proposes `new_owner` as the auction owner. The transfer completes once
+    /// they accept it, unless it expires first.
+    TransferOwnership {
+        auction_id: u64,
+        new_owner: String,
+        expiry: Option<Expiration>,
+    },
+    AcceptOwnership {
+        auction_id: u64,
+    },
+    /// Gives up owner privileges for good. Payouts still go to the last owner
+    /// and anyone can close the auction once bidding has ended.
+    RenounceOwnership {
+        auction_id: u64,
+    },
+    /// Stops bidding and closing until the auction is unpaused. Sealed bids
+    /// can still be revealed and the commission withdrawn, and anyone can
+    /// close the auction once the settlement deadline has passed. Callable
+    /// by the owner and the guardian.
+    Pause {
+        auction_id: u64,
+        reason: Option<String>,
+    },
+    Unpause {
+        auction_id: u64,
+    },
+}
+
+#[cw_serde]
+pub enum ReceiveMsg {
+    Bid {
+        auction_id: u64
+    },
+    Buy {
+        auction_id: u64
+    },
+    Commit {
+        auction_id: u64,
+        commitment: Binary
+    },
 }
 
 #[cw_serde]
 pub struct ValueResponse {
     pub open: bool,
-    pub token: String,
+    pub status: AuctionStatus,
+    pub token: Denom,
     pub owner: Addr,
     pub part: Decimal,
-    pub bids: Vec<Bid>,
+    pub end_time: Option<Timestamp>,
+    pub end_height: Option<u64>,
+    pub settlement_deadline: Option<Timestamp>,
+    pub keeper_reward: Decimal,
+    pub min_increment: Uint128,
+    pub min_increment_percent: Decimal,
+    pub reserve: Option<Reserve>,
+    pub buy_now_price: Option<Uint128>,
+    pub mode: AuctionMode,
+    pub settlement: Settlement,
+    pub extension_window: u64,
+    pub extension_duration: u64,
+    pub max_end_time: Option<Timestamp>,
+    pub fee_collector: Option<Addr>,
+    pub commission_mode: CommissionMode,
+    pub refund_commission_on_cancel: bool,
+    pub denom_rates: Vec<DenomRate>,
+    pub owner_renounced: bool,
+    pub nft: Option<Nft>,
     pub highest_bid: Bid
 }
 
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+    pub owner_renounced: bool,
+    pub token: Denom,
+    pub part: Decimal,
+}
+
+#[cw_serde]
+pub struct PendingOwnerResponse {
+    pub new_owner: Option<Addr>,
+    pub expiry: Option<Expiration>,
+}
+
+#[cw_serde]
+pub struct StatusResponse {
+    pub status: AuctionStatus,
+    /// Bidding deadline was reached, but the auction may still wait to be
+    /// closed.
+    pub ended: bool,
+    pub end_time: Option<Timestamp>,
+    pub end_height: Option<u64>,
+    pub settlement_deadline: Option<Timestamp>,
+    pub paused: bool,
+    pub pause_reason: Option<String>,
+}
+
+#[cw_serde]
+pub struct HighestBidResponse {
+    /// None until the first bid is placed.
+    pub bid: Option<Bid>,
+}
+
+#[cw_serde]
+pub struct BidOfResponse {
+    pub amount: Option<Uint128>,
+}
+
+#[cw_serde]
+pub enum AuctionStatus {
+    Open,
+    Sold,
+    /// Closed without a winner. Every bidder can retract their bids.
+    Unsold,
+    /// Aborted by the owner. Every bidder can retract their bids and
+    /// unrevealed deposits.
+    Cancelled,
+}
+
+#[cw_serde]
+pub enum Reserve {
+    Public(Uint128),
+    /// Commitment to the reserve price created with `commitment`. Unless the
+    /// owner reveals it before closing, the auction ends unsold.
+    Hidden(Binary),
+}
+
+/// Hash committing to `amount` without disclosing it - sha256 of
+/// `"{amount}:{salt}"`.
+pub fn commitment(amount: Uint128, salt: &str) -> Binary {
+    Binary::from(Sha256::digest(format!("{amount}:{salt}")).to_vec())
+}
+
+/// Commitment to a sealed bid of `bidder` - sha256 of
+/// `"{auction_id}:{bidder}:{amount}:{salt}"`. Binding it to the bidder keeps
+/// others from copying it and revealing it first.
+pub fn bid_commitment(auction_id: u64, bidder: &Addr, amount: Uint128, salt: &str) -> Binary {
+    Binary::from(Sha256::digest(format!("{auction_id}:{bidder}:{amount}:{salt}")).to_vec())
+}
+
+#[cw_serde]
+pub struct BidsResponse {
+    pub bids: Vec<Bid>,
+}
+
+#[cw_serde]
+pub struct AccruedCommissionResponse {
+    pub amount: Uint128,
+    /// Commission accrued in the additionally accepted denoms.
+    pub denoms: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct BidHistoryResponse {
+    pub bids: Vec<BidRecord>,
+}
+
+#[cw_serde]
+pub struct BidRecord {
+    pub id: u64,
+    pub auction_id: u64,
+    pub bidder: Addr,
+    /// Amount the bid added to the bidder's total. Both are net of the
+    /// commission charged on the bid.
+    pub increment: Uint128,
+    pub total: Uint128,
+    pub height: u64,
+    pub time: Timestamp,
+    pub tx_index: Option<u32>,
+}
+
+#[cw_serde]
+pub struct CurrentPriceResponse {
+    /// `None` unless it is a dutch auction.
+    pub price: Option<Uint128>,
+}
+
+#[cw_serde]
+pub enum AuctionMode {
+    /// Ascending bids, the highest one wins when the auction is closed.
+    English,
+    /// Price falls from `start_price` to `floor_price` and the first buyer
+    /// wins.
+    Dutch {
+        start_price: Uint128,
+        floor_price: Uint128,
+        decay: PriceDecay,
+    },
+    /// Bidders commit to hidden bids until `commit_end` and reveal them until
+    /// `reveal_end`. Deposits of bids that were never revealed are refunded
+    /// if `refund_unrevealed` is set, otherwise they go to the owner.
+    Sealed {
+        commit_end: Timestamp,
+        reveal_end: Timestamp,
+        refund_unrevealed: bool,
+    },
+}
+
+#[cw_serde]
+pub enum Settlement {
+    /// Winner pays their own bid.
+    FirstPrice,
+    /// Winner pays the second highest bid plus `increment`, capped at their
+    /// own bid. The difference is refunded on close.
+    SecondPrice { increment: Option<Uint128> },
+}
+
+#[cw_serde]
+pub enum CommissionMode {
+    /// `part` of every bid goes to the commission, losing bids included.
+    PerBid,
+    /// Bids are kept whole and `part` is taken from the winning price when
+    /// the auction is sold.
+    OnSettlement,
+}
+
+/// Native denom a bid can be placed in besides the auction token. A coin of
+/// it counts as `rate` units of the auction token.
+#[cw_serde]
+pub struct DenomRate {
+    pub denom: String,
+    pub rate: Decimal,
+}
+
+#[cw_serde]
+pub enum PriceDecay {
+    /// Price reaches the floor `duration` seconds after the auction starts.
+    Linear { duration: u64 },
+    /// Price drops by `step` every `interval` seconds.
+    Stepwise { interval: u64, step: Uint128 },
+}
+
+#[cw_serde]
+pub struct Nft {
+    pub contract: Addr,
+    pub token_id: String,
+    /// Gets the NFT back if the auction ends without a sale.
+    pub seller: Addr,
+}
+
 #[cw_serde]
 pub struct Bid {
     pub addr: Addr,
     pub amount: Uint128,
-}
\ No newline at end of file
+}
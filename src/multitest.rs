@@ -1,12 +1,18 @@
-use cosmwasm_std::{Addr, Coin, Decimal, StdResult};
-use cw_multi_test::{App, Executor};
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, StdResult, Uint128};
+use cw20::{Denom, Expiration};
+use cw_multi_test::{App, AppResponse, Executor};
 
 use crate::{
     error::ContractError,
-    msg::{ExecMsg, InstantiateMsg, QueryMsg, ValueResponse},
+    msg::{
+        AccruedCommissionResponse, Bid, BidHistoryResponse, BidOfResponse, BidsResponse,
+        ConfigResponse, CreateAuctionMsg, CurrentPriceResponse, ExecMsg, HighestBidResponse,
+        InstantiateMsg, MigrateMsg, PendingOwnerResponse, QueryMsg, StatusResponse, ValueResponse,
+    },
 };
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests;
 
 pub struct BiddingPlatform(Addr);
@@ -22,38 +28,181 @@ impl BiddingPlatform {
         code_id: u64,
         sender: &Addr,
         label: &str,
+    ) -> StdResult<BiddingPlatform> {
+        Self::instantiate_with_msg(app, code_id, sender, label, InstantiateMsg { guardian: None })
+    }
+
+    #[track_caller]
+    pub fn instantiate_with_msg(
+        app: &mut App,
+        code_id: u64,
+        sender: &Addr,
+        label: &str,
+        msg: InstantiateMsg,
+    ) -> StdResult<BiddingPlatform> {
+        app.instantiate_contract(code_id, sender.clone(), &msg, &[], label, None)
+            .map_err(|err| err.downcast().unwrap())
+            .map(BiddingPlatform)
+    }
+
+    #[track_caller]
+    pub fn migrate(&self, app: &mut App, sender: &Addr, code_id: u64) -> Result<(), ContractError> {
+        app.migrate_contract(sender.clone(), self.0.clone(), &MigrateMsg {}, code_id)
+            .map_err(|err| err.downcast().unwrap())
+            .map(|_| ())
+    }
+
+    #[track_caller]
+    pub fn create_auction(
+        &self,
+        app: &mut App,
+        sender: &Addr,
         owner: Option<&Addr>,
         part: Decimal,
-        token: String,
-    ) -> StdResult<BiddingPlatform> {
-        app.instantiate_contract(
-            code_id,
-            sender.clone(),
-            &InstantiateMsg {
+        token: Denom,
+    ) -> Result<u64, ContractError> {
+        self.create_auction_with_msg(
+            app,
+            sender,
+            CreateAuctionMsg {
                 owner: owner.map(Addr::to_string),
-                part: part,
-                token: token,
+                part,
+                token,
+                end_time: None,
+                end_height: None,
+                allow_early_close: false,
+                settlement_deadline: None,
+                keeper_reward: None,
+                min_increment: None,
+                min_increment_percent: None,
+                reserve: None,
+                buy_now_price: None,
+                mode: None,
+                settlement: None,
+                extension_window: None,
+                extension_duration: None,
+                max_end_time: None,
+                fee_collector: None,
+                commission_mode: None,
+                refund_commission_on_cancel: false,
+                denom_rates: None,
             },
-            &[],
-            label,
-            None,
         )
-        .map_err(|err| err.downcast().unwrap())
-        .map(BiddingPlatform)
     }
 
     #[track_caller]
-    pub fn bid(&self, app: &mut App, sender: &Addr, funds: &[Coin]) -> Result<(), ContractError> {
-        app.execute_contract(sender.clone(), self.0.clone(), &ExecMsg::Bid {}, funds)
+    pub fn create_auction_with_msg(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        msg: CreateAuctionMsg,
+    ) -> Result<u64, ContractError> {
+        let resp = app
+            .execute_contract(
+                sender.clone(),
+                self.0.clone(),
+                &ExecMsg::CreateAuction(Box::new(msg)),
+                &[],
+            )
             .map_err(|err| err.downcast::<ContractError>().unwrap())?;
 
+        Ok(auction_id(&resp))
+    }
+
+    #[track_caller]
+    pub fn bid(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        auction_id: u64,
+        funds: &[Coin],
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::Bid { auction_id },
+            funds,
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
         Ok(())
     }
 
     #[track_caller]
-    pub fn close(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
-        app.execute_contract(sender.clone(), self.0.clone(), &ExecMsg::Close {}, &[])
-            .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+    pub fn buy(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        auction_id: u64,
+        funds: &[Coin],
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::Buy { auction_id },
+            funds,
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn commit(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        auction_id: u64,
+        commitment: Binary,
+        funds: &[Coin],
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::Commit {
+                auction_id,
+                commitment,
+            },
+            funds,
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn reveal(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        auction_id: u64,
+        amount: Uint128,
+        salt: &str,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::Reveal {
+                auction_id,
+                amount,
+                salt: salt.to_string(),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn close(&self, app: &mut App, sender: &Addr, auction_id: u64) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::Close { auction_id },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
 
         Ok(())
     }
@@ -63,12 +212,89 @@ impl BiddingPlatform {
         &self,
         app: &mut App,
         sender: &Addr,
+        auction_id: u64,
         receiver: Option<String>,
     ) -> Result<(), ContractError> {
         app.execute_contract(
             sender.clone(),
             self.0.clone(),
-            &ExecMsg::Retract { receiver: receiver },
+            &ExecMsg::Retract {
+                auction_id,
+                receiver,
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn reveal_reserve(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        auction_id: u64,
+        amount: Uint128,
+        salt: &str,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::RevealReserve {
+                auction_id,
+                amount,
+                salt: salt.to_string(),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn withdraw_commission(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        auction_id: u64,
+        amount: Option<u128>,
+        receiver: Option<&Addr>,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::WithdrawCommission {
+                auction_id,
+                amount: amount.map(Uint128::new),
+                receiver: receiver.map(Addr::to_string),
+                denom: None,
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn withdraw_denom_commission(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        auction_id: u64,
+        denom: &str,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::WithdrawCommission {
+                auction_id,
+                amount: None,
+                receiver: None,
+                denom: Some(denom.to_owned()),
+            },
             &[],
         )
         .map_err(|err| err.downcast::<ContractError>().unwrap())?;
@@ -76,8 +302,295 @@ impl BiddingPlatform {
         Ok(())
     }
 
-    pub fn query_value(&self, app: &App) -> StdResult<ValueResponse> {
+    #[track_caller]
+    pub fn set_denom_rate(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        auction_id: u64,
+        denom: &str,
+        rate: Option<Decimal>,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::SetDenomRate {
+                auction_id,
+                denom: denom.to_owned(),
+                rate,
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn cancel(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        auction_id: u64,
+        reason: Option<&str>,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::Cancel {
+                auction_id,
+                reason: reason.map(str::to_owned),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn transfer_ownership(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        auction_id: u64,
+        new_owner: &Addr,
+        expiry: Option<Expiration>,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::TransferOwnership {
+                auction_id,
+                new_owner: new_owner.to_string(),
+                expiry,
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn accept_ownership(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        auction_id: u64,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::AcceptOwnership { auction_id },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn renounce_ownership(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        auction_id: u64,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::RenounceOwnership { auction_id },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn pause(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        auction_id: u64,
+        reason: Option<&str>,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::Pause {
+                auction_id,
+                reason: reason.map(str::to_owned),
+            },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn unpause(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        auction_id: u64,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(),
+            self.0.clone(),
+            &ExecMsg::Unpause { auction_id },
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    pub fn query_value(&self, app: &App, auction_id: u64) -> StdResult<ValueResponse> {
         app.wrap()
-            .query_wasm_smart(self.0.clone(), &QueryMsg::Value {})
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Value { auction_id })
     }
+
+    pub fn query_config(&self, app: &App, auction_id: u64) -> StdResult<ConfigResponse> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Config { auction_id })
+    }
+
+    pub fn query_pending_owner(&self, app: &App, auction_id: u64) -> StdResult<PendingOwnerResponse> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::PendingOwner { auction_id })
+    }
+
+    pub fn query_status(&self, app: &App, auction_id: u64) -> StdResult<StatusResponse> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::Status { auction_id })
+    }
+
+    pub fn query_highest_bid(&self, app: &App, auction_id: u64) -> StdResult<HighestBidResponse> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::HighestBid { auction_id })
+    }
+
+    pub fn query_bid_of(
+        &self,
+        app: &App,
+        auction_id: u64,
+        address: &Addr,
+    ) -> StdResult<BidOfResponse> {
+        app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::BidOf {
+                auction_id,
+                address: address.to_string(),
+            },
+        )
+    }
+
+    pub fn query_bids(
+        &self,
+        app: &App,
+        auction_id: u64,
+        start_after: Option<&Addr>,
+        limit: Option<u32>,
+    ) -> StdResult<BidsResponse> {
+        app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::Bids {
+                auction_id,
+                start_after: start_after.map(Addr::to_string),
+                limit,
+            },
+        )
+    }
+
+    pub fn query_highest_bid_at_height(
+        &self,
+        app: &App,
+        auction_id: u64,
+        height: u64,
+    ) -> StdResult<HighestBidResponse> {
+        app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::HighestBidAtHeight { auction_id, height },
+        )
+    }
+
+    pub fn query_bid_of_at_height(
+        &self,
+        app: &App,
+        auction_id: u64,
+        address: &Addr,
+        height: u64,
+    ) -> StdResult<BidOfResponse> {
+        app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::BidOfAtHeight {
+                auction_id,
+                address: address.to_string(),
+                height,
+            },
+        )
+    }
+
+    pub fn query_top_bids(
+        &self,
+        app: &App,
+        auction_id: u64,
+        start_after: Option<&Bid>,
+        limit: Option<u32>,
+    ) -> StdResult<BidsResponse> {
+        app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::TopBids {
+                auction_id,
+                start_after: start_after.cloned(),
+                limit,
+            },
+        )
+    }
+
+    pub fn query_accrued_commission(
+        &self,
+        app: &App,
+        auction_id: u64,
+    ) -> StdResult<AccruedCommissionResponse> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::AccruedCommission { auction_id })
+    }
+
+    pub fn query_bid_history(
+        &self,
+        app: &App,
+        auction_id: u64,
+        bidder: Option<&Addr>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<BidHistoryResponse> {
+        app.wrap().query_wasm_smart(
+            self.0.clone(),
+            &QueryMsg::BidHistory {
+                auction_id,
+                bidder: bidder.map(Addr::to_string),
+                start_after,
+                limit,
+            },
+        )
+    }
+
+    pub fn query_current_price(&self, app: &App, auction_id: u64) -> StdResult<CurrentPriceResponse> {
+        app.wrap()
+            .query_wasm_smart(self.0.clone(), &QueryMsg::CurrentPrice { auction_id })
+    }
+}
+
+/// Reads the id of a newly created auction from the response attributes.
+pub fn auction_id(resp: &AppResponse) -> u64 {
+    resp.events
+        .iter()
+        .flat_map(|event| &event.attributes)
+        .find(|attr| attr.key == "auction_id")
+        .and_then(|attr| attr.value.parse().ok())
+        .unwrap()
 }
@@ -1,14 +1,116 @@
-use cosmwasm_std::{coins, Addr, Decimal, Empty, Uint128};
-use cw_multi_test::{App, Contract, ContractWrapper};
+use cosmwasm_std::{
+    coin, coins, to_json_binary, Addr, Binary, Coin, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Response, StdResult, Uint128,
+};
+use cw2::set_contract_version;
+use cw20::{
+    BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg, Denom, Expiration,
+};
+use cw721::{Cw721ExecuteMsg, Cw721QueryMsg, Cw721ReceiveMsg, OwnerOfResponse};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
 
-use crate::{execute, instantiate, msg::Bid, multitest::BiddingPlatform, query, error::ContractError};
+use crate::{
+    contract::migrate::v0_1,
+    execute, instantiate, migrate,
+    msg::{
+        bid_commitment, commitment, AuctionMode, AuctionStatus, Bid, CommissionMode,
+        ConfigResponse, CreateAuctionMsg, DenomRate, ExecMsg, InstantiateMsg, Nft,
+        PendingOwnerResponse, PriceDecay, ReceiveMsg, Reserve, Settlement, StatusResponse,
+    },
+    multitest::{auction_id, BiddingPlatform},
+    query, error::ContractError,
+};
 
 fn bidding_platform() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(execute, instantiate, query);
+    let contract = ContractWrapper::new(execute, instantiate, query).with_migrate(migrate);
+    Box::new(contract)
+}
+
+/// Contract doing nothing but setting up storage on instantiation, used to
+/// stand in for other deployed versions.
+fn stub_platform(
+    instantiate: fn(DepsMut, Env, MessageInfo, Empty) -> StdResult<Response>,
+) -> Box<dyn Contract<Empty>> {
+    fn execute(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        Ok(Binary::default())
+    }
+
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+/// 0.1.0 deployment with a single auction stored in the old layout.
+fn legacy_platform() -> Box<dyn Contract<Empty>> {
+    stub_platform(|deps, _env, info, _msg| {
+        set_contract_version(deps.storage, env!("CARGO_PKG_NAME"), "0.1.0")?;
+
+        v0_1::STATE.save(
+            deps.storage,
+            &v0_1::State {
+                open: true,
+                token: ATOM.to_owned(),
+                part: Decimal::percent(10),
+            },
+        )?;
+        v0_1::OWNER.save(deps.storage, &info.sender)?;
+
+        let bidder = Addr::unchecked("sender1");
+        v0_1::BIDS.save(deps.storage, &bidder, &Uint128::new(18))?;
+        v0_1::HIGHEST_BID.save(deps.storage, &(bidder, Uint128::new(18)))?;
+
+        Ok(Response::new())
+    })
+}
+
+fn cw20() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    );
+    Box::new(contract)
+}
+
+fn cw721() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw721_base::entry::execute,
+        cw721_base::entry::instantiate,
+        cw721_base::entry::query,
+    );
     Box::new(contract)
 }
 
 const ATOM: &str = "atom";
+const OSMO: &str = "uosmo";
+
+fn auction_msg(owner: &Addr) -> CreateAuctionMsg {
+    CreateAuctionMsg {
+        owner: Some(owner.to_string()),
+        part: Decimal::percent(10),
+        token: Denom::Native(ATOM.to_string()),
+        end_time: None,
+        end_height: None,
+        allow_early_close: false,
+        settlement_deadline: None,
+        keeper_reward: None,
+        min_increment: None,
+        min_increment_percent: None,
+        reserve: None,
+        buy_now_price: None,
+        mode: None,
+        settlement: None,
+        extension_window: None,
+        extension_duration: None,
+        max_end_time: None,
+        fee_collector: None,
+        commission_mode: None,
+        refund_commission_on_cancel: false,
+        denom_rates: None,
+    }
+}
 
 #[test]
 fn query_value() {
@@ -22,19 +124,26 @@ fn query_value() {
         contract_id,
         &sender,
         "Bidding contract",
-        None,
-        Decimal::percent(10),
-        ATOM.to_string(),
     )
     .unwrap();
 
-    let resp = contract.query_value(&app).unwrap();
+    let auction_id = contract
+        .create_auction(
+            &mut app,
+            &sender,
+            None,
+            Decimal::percent(10),
+            Denom::Native(ATOM.to_string()),
+        )
+        .unwrap();
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
 
     assert_eq!(resp.open, true);
-    assert_eq!(resp.token, "atom");
+    assert_eq!(resp.token, Denom::Native(ATOM.to_string()));
     assert_eq!(resp.owner, sender);
     assert_eq!(resp.part, Decimal::percent(10));
-    assert_eq!(resp.bids, vec![]);
+    assert_eq!(contract.query_bids(&app, auction_id, None, None).unwrap().bids, vec![]);
     assert_eq!(
         resp.highest_bid,
         Bid {
@@ -57,19 +166,26 @@ fn create_contract_with_owner() {
         contract_id,
         &sender,
         "Bidding contract",
-        Some(&owner),
-        Decimal::percent(10),
-        ATOM.to_string(),
     )
     .unwrap();
 
-    let resp = contract.query_value(&app).unwrap();
+    let auction_id = contract
+        .create_auction(
+            &mut app,
+            &sender,
+            Some(&owner),
+            Decimal::percent(10),
+            Denom::Native(ATOM.to_string()),
+        )
+        .unwrap();
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
 
     assert_eq!(resp.open, true);
-    assert_eq!(resp.token, "atom");
+    assert_eq!(resp.token, Denom::Native(ATOM.to_string()));
     assert_eq!(resp.owner, owner);
     assert_eq!(resp.part, Decimal::percent(10));
-    assert_eq!(resp.bids, vec![]);
+    assert_eq!(contract.query_bids(&app, auction_id, None, None).unwrap().bids, vec![]);
     assert_eq!(
         resp.highest_bid,
         Bid {
@@ -110,22 +226,29 @@ pub fn bid_close_retract() {
         contract_id,
         &sender1,
         "Bidding contract",
-        Some(&owner),
-        Decimal::percent(10),
-        ATOM.to_string(),
     )
     .unwrap();
 
-    contract.bid(&mut app, &sender2, &coins(10, ATOM)).unwrap();
+    let auction_id = contract
+        .create_auction(
+            &mut app,
+            &sender1,
+            Some(&owner),
+            Decimal::percent(10),
+            Denom::Native(ATOM.to_string()),
+        )
+        .unwrap();
+
+    contract.bid(&mut app, &sender2, auction_id, &coins(10, ATOM)).unwrap();
 
-    let resp = contract.query_value(&app).unwrap();
+    let resp = contract.query_value(&app, auction_id).unwrap();
 
     assert_eq!(resp.open, true);
-    assert_eq!(resp.token, "atom");
+    assert_eq!(resp.token, Denom::Native(ATOM.to_string()));
     assert_eq!(resp.owner, owner);
     assert_eq!(resp.part, Decimal::percent(10));
     assert_eq!(
-        resp.bids,
+        contract.query_bids(&app, auction_id, None, None).unwrap().bids,
         vec![Bid {
             addr: sender2.clone(),
             amount: Uint128::new(9)
@@ -138,6 +261,9 @@ pub fn bid_close_retract() {
             amount: Uint128::new(9)
         }
     );
+    contract
+        .withdraw_commission(&mut app, &owner, auction_id, None, None)
+        .unwrap();
     assert_eq!(
         app.wrap().query_all_balances(&owner).unwrap(),
         coins(1, ATOM)
@@ -155,23 +281,23 @@ pub fn bid_close_retract() {
         coins(9, ATOM)
     );
 
-    let err = contract.bid(&mut app, &sender3, &coins(5, ATOM)).unwrap_err();
+    let err = contract.bid(&mut app, &sender3, auction_id, &coins(5, ATOM)).unwrap_err();
 
     assert_eq!(
         err,
         ContractError::BidLow { highest: resp.highest_bid.amount, sender_total: Uint128::zero() }
     );
 
-    contract.bid(&mut app, &sender1, &coins(20, ATOM)).unwrap();
+    contract.bid(&mut app, &sender1, auction_id, &coins(20, ATOM)).unwrap();
 
-    let mut resp = contract.query_value(&app).unwrap();
+    let mut resp = contract.query_value(&app, auction_id).unwrap();
 
     assert_eq!(resp.open, true);
-    assert_eq!(resp.token, "atom");
+    assert_eq!(resp.token, Denom::Native(ATOM.to_string()));
     assert_eq!(resp.owner, owner);
     assert_eq!(resp.part, Decimal::percent(10));
     assert_eq!(
-        resp.bids,
+        contract.query_bids(&app, auction_id, None, None).unwrap().bids,
         vec![Bid {
             addr: sender1.clone(),
             amount: Uint128::new(18)
@@ -188,6 +314,9 @@ pub fn bid_close_retract() {
             amount: Uint128::new(18)
         }
     );
+    contract
+        .withdraw_commission(&mut app, &owner, auction_id, None, None)
+        .unwrap();
     assert_eq!(
         app.wrap().query_all_balances(&owner).unwrap(),
         coins(3, ATOM)
@@ -205,12 +334,12 @@ pub fn bid_close_retract() {
         coins(27, ATOM)
     );
 
-    contract.close(&mut app, &owner).unwrap();
-    contract.retract(&mut app, &sender2, Some(sender1.to_string())).unwrap();
+    contract.close(&mut app, &owner, auction_id).unwrap();
+    contract.retract(&mut app, &sender2, auction_id, Some(sender1.to_string())).unwrap();
 
-    resp = contract.query_value(&app).unwrap();
+    resp = contract.query_value(&app, auction_id).unwrap();
     assert_eq!(resp.open, false);
-    assert_eq!(resp.bids, vec![]);
+    assert_eq!(contract.query_bids(&app, auction_id, None, None).unwrap().bids, vec![]);
     assert_eq!(
         app.wrap().query_all_balances(&sender1).unwrap(),
         coins(9, ATOM)
@@ -238,19 +367,26 @@ fn close() {
         contract_id,
         &sender,
         "Bidding contract",
-        Some(&owner),
-        Decimal::percent(10),
-        ATOM.to_string(),
     )
     .unwrap();
 
-    let mut resp = contract.query_value(&app).unwrap();
+    let auction_id = contract
+        .create_auction(
+            &mut app,
+            &sender,
+            Some(&owner),
+            Decimal::percent(10),
+            Denom::Native(ATOM.to_string()),
+        )
+        .unwrap();
+
+    let mut resp = contract.query_value(&app, auction_id).unwrap();
 
     assert_eq!(resp.open, true);
-    assert_eq!(resp.token, "atom");
+    assert_eq!(resp.token, Denom::Native(ATOM.to_string()));
     assert_eq!(resp.owner, owner.clone());
     assert_eq!(resp.part, Decimal::percent(10));
-    assert_eq!(resp.bids, vec![]);
+    assert_eq!(contract.query_bids(&app, auction_id, None, None).unwrap().bids, vec![]);
     assert_eq!(
         resp.highest_bid,
         Bid {
@@ -259,7 +395,7 @@ fn close() {
         }
     );
 
-    let mut err = contract.close(&mut app, &sender).unwrap_err();
+    let mut err = contract.close(&mut app, &sender, auction_id).unwrap_err();
     assert_eq!(
         err,
         ContractError::Unauthorized {
@@ -267,11 +403,11 @@ fn close() {
         }
     );
 
-    contract.close(&mut app, &owner).unwrap();
-    resp = contract.query_value(&app).unwrap();
+    contract.close(&mut app, &owner, auction_id).unwrap();
+    resp = contract.query_value(&app, auction_id).unwrap();
     assert_eq!(resp.open, false);
 
-    err = contract.close(&mut app, &owner).unwrap_err();
+    err = contract.close(&mut app, &owner, auction_id).unwrap_err();
     assert_eq!(
         err,
         ContractError::BiddingAlreadyClosed {}
@@ -298,19 +434,26 @@ fn close_with_bids() {
         contract_id,
         &sender,
         "Bidding contract",
-        Some(&owner),
-        Decimal::percent(10),
-        ATOM.to_string(),
     )
     .unwrap();
 
-    let mut resp = contract.query_value(&app).unwrap();
+    let auction_id = contract
+        .create_auction(
+            &mut app,
+            &sender,
+            Some(&owner),
+            Decimal::percent(10),
+            Denom::Native(ATOM.to_string()),
+        )
+        .unwrap();
+
+    let mut resp = contract.query_value(&app, auction_id).unwrap();
 
     assert_eq!(resp.open, true);
-    assert_eq!(resp.token, "atom");
+    assert_eq!(resp.token, Denom::Native(ATOM.to_string()));
     assert_eq!(resp.owner, owner.clone());
     assert_eq!(resp.part, Decimal::percent(10));
-    assert_eq!(resp.bids, vec![]);
+    assert_eq!(contract.query_bids(&app, auction_id, None, None).unwrap().bids, vec![]);
     assert_eq!(
         resp.highest_bid,
         Bid {
@@ -319,7 +462,7 @@ fn close_with_bids() {
         }
     );
 
-    let mut err = contract.close(&mut app, &sender).unwrap_err();
+    let mut err = contract.close(&mut app, &sender, auction_id).unwrap_err();
     assert_eq!(
         err,
         ContractError::Unauthorized {
@@ -327,16 +470,16 @@ fn close_with_bids() {
         }
     );
 
-    contract.bid(&mut app, &sender, &coins(10, ATOM)).unwrap();
+    contract.bid(&mut app, &sender, auction_id, &coins(10, ATOM)).unwrap();
 
-    resp = contract.query_value(&app).unwrap();
+    resp = contract.query_value(&app, auction_id).unwrap();
 
     assert_eq!(resp.open, true);
-    assert_eq!(resp.token, "atom");
+    assert_eq!(resp.token, Denom::Native(ATOM.to_string()));
     assert_eq!(resp.owner, owner);
     assert_eq!(resp.part, Decimal::percent(10));
     assert_eq!(
-        resp.bids,
+        contract.query_bids(&app, auction_id, None, None).unwrap().bids,
         vec![Bid {
             addr: sender.clone(),
             amount: Uint128::new(9)
@@ -349,6 +492,9 @@ fn close_with_bids() {
             amount: Uint128::new(9)
         }
     );
+    contract
+        .withdraw_commission(&mut app, &owner, auction_id, None, None)
+        .unwrap();
     assert_eq!(
         app.wrap().query_all_balances(&owner).unwrap(),
         coins(1, ATOM)
@@ -362,12 +508,12 @@ fn close_with_bids() {
         coins(9, ATOM)
     );
 
-    contract.close(&mut app, &owner).unwrap();
+    contract.close(&mut app, &owner, auction_id).unwrap();
 
-    resp = contract.query_value(&app).unwrap();
+    resp = contract.query_value(&app, auction_id).unwrap();
 
     assert_eq!(resp.open, false);
-    assert_eq!(resp.bids, vec![]);
+    assert_eq!(contract.query_bids(&app, auction_id, None, None).unwrap().bids, vec![]);
     assert_eq!(
         app.wrap().query_all_balances(&owner).unwrap(),
         coins(10, ATOM)
@@ -377,10 +523,2996 @@ fn close_with_bids() {
         vec![]
     );
 
-    err = contract.close(&mut app, &owner).unwrap_err();
+    err = contract.close(&mut app, &owner, auction_id).unwrap_err();
     assert_eq!(
         err,
         ContractError::BiddingAlreadyClosed {}
     );
 
-}
\ No newline at end of file
+}
+
+#[test]
+fn bid_after_end_time() {
+    let sender = Addr::unchecked("sender");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+    let end_time = app.block_info().time.plus_seconds(100);
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &sender,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &sender,
+            CreateAuctionMsg {
+                end_time: Some(end_time),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
+    assert_eq!(resp.end_time, Some(end_time));
+    assert_eq!(resp.end_height, None);
+
+    contract.bid(&mut app, &sender, auction_id, &coins(10, ATOM)).unwrap();
+
+    let err = contract.close(&mut app, &owner, auction_id).unwrap_err();
+    assert_eq!(err, ContractError::EarlyCloseErr);
+
+    app.update_block(|block| block.time = end_time);
+
+    let err = contract.bid(&mut app, &sender, auction_id, &coins(10, ATOM)).unwrap_err();
+    assert_eq!(err, ContractError::BiddingEnded);
+
+    contract.close(&mut app, &owner, auction_id).unwrap();
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
+    assert!(!resp.open);
+    contract
+        .withdraw_commission(&mut app, &owner, auction_id, None, None)
+        .unwrap();
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        coins(10, ATOM)
+    );
+}
+
+#[test]
+fn close_after_end_height() {
+    let sender = Addr::unchecked("sender");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+    let end_height = app.block_info().height + 10;
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &sender,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &sender,
+            CreateAuctionMsg {
+                end_height: Some(end_height),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    let err = contract.close(&mut app, &owner, auction_id).unwrap_err();
+    assert_eq!(err, ContractError::EarlyCloseErr);
+
+    app.update_block(|block| block.height = end_height);
+
+    let err = contract.bid(&mut app, &sender, auction_id, &coins(10, ATOM)).unwrap_err();
+    assert_eq!(err, ContractError::BiddingEnded);
+
+    contract.close(&mut app, &owner, auction_id).unwrap();
+}
+
+#[test]
+fn early_close() {
+    let sender = Addr::unchecked("sender");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::default();
+
+    let contract_id = app.store_code(bidding_platform());
+    let end_time = app.block_info().time.plus_seconds(100);
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &sender,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let err = contract
+        .create_auction_with_msg(
+            &mut app,
+            &sender,
+            CreateAuctionMsg {
+                end_time: Some(end_time),
+                settlement_deadline: Some(end_time.minus_seconds(1)),
+                allow_early_close: true,
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::SettlementBeforeEnd);
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &sender,
+            CreateAuctionMsg {
+                end_time: Some(end_time),
+                allow_early_close: true,
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    contract.close(&mut app, &owner, auction_id).unwrap();
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
+    assert!(!resp.open);
+}
+
+#[test]
+fn keeper_close_after_settlement_deadline() {
+    let sender = Addr::unchecked("sender");
+    let owner = Addr::unchecked("owner");
+    let keeper = Addr::unchecked("keeper");
+    let collector = Addr::unchecked("collector");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(100, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+    let end_time = app.block_info().time.plus_seconds(100);
+    let settlement_deadline = end_time.plus_seconds(100);
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &sender,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &sender,
+            CreateAuctionMsg {
+                end_time: Some(end_time),
+                settlement_deadline: Some(settlement_deadline),
+                keeper_reward: Some(Decimal::percent(50)),
+                fee_collector: Some(collector.to_string()),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    contract.bid(&mut app, &sender, auction_id, &coins(100, ATOM)).unwrap();
+
+    app.update_block(|block| block.time = end_time);
+
+    let err = contract.close(&mut app, &keeper, auction_id).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized {
+            owner: owner.to_string()
+        }
+    );
+
+    app.update_block(|block| block.time = settlement_deadline);
+
+    contract.close(&mut app, &keeper, auction_id).unwrap();
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
+    assert!(!resp.open);
+    assert_eq!(contract.query_bids(&app, auction_id, None, None).unwrap().bids, vec![]);
+
+    // The reward comes out of the commission, the owner gets the whole bid
+    contract
+        .withdraw_commission(&mut app, &collector, auction_id, None, None)
+        .unwrap();
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        coins(90, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(&collector).unwrap(),
+        coins(5, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(&keeper).unwrap(),
+        coins(5, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(contract.addr()).unwrap(),
+        vec![]
+    );
+}
+
+#[test]
+fn multiple_auctions() {
+    let sender = Addr::unchecked("sender");
+    let owner1 = Addr::unchecked("owner1");
+    let owner2 = Addr::unchecked("owner2");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(30, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &sender,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let first = contract
+        .create_auction(&mut app, &owner1, None, Decimal::percent(10), Denom::Native(ATOM.to_string()))
+        .unwrap();
+    let second = contract
+        .create_auction(&mut app, &owner2, None, Decimal::percent(10), Denom::Native(ATOM.to_string()))
+        .unwrap();
+
+    assert_eq!(first, 0);
+    assert_eq!(second, 1);
+
+    contract.bid(&mut app, &sender, first, &coins(10, ATOM)).unwrap();
+    contract.bid(&mut app, &sender, second, &coins(20, ATOM)).unwrap();
+
+    let resp = contract.query_value(&app, first).unwrap();
+    assert_eq!(resp.owner, owner1);
+    assert_eq!(
+        resp.highest_bid,
+        Bid {
+            addr: sender.clone(),
+            amount: Uint128::new(9)
+        }
+    );
+
+    let resp = contract.query_value(&app, second).unwrap();
+    assert_eq!(resp.owner, owner2);
+    assert_eq!(
+        resp.highest_bid,
+        Bid {
+            addr: sender.clone(),
+            amount: Uint128::new(18)
+        }
+    );
+
+    contract.close(&mut app, &owner2, second).unwrap();
+
+    assert!(contract.query_value(&app, first).unwrap().open);
+    assert!(!contract.query_value(&app, second).unwrap().open);
+    contract
+        .withdraw_commission(&mut app, &owner2, second, None, None)
+        .unwrap();
+    assert_eq!(
+        app.wrap().query_all_balances(&owner2).unwrap(),
+        coins(20, ATOM)
+    );
+    assert_eq!(
+        contract.query_accrued_commission(&app, first).unwrap().amount,
+        Uint128::new(1)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(contract.addr()).unwrap(),
+        coins(10, ATOM)
+    );
+}
+
+fn mint_nft(app: &mut App, minter: &Addr, owner: &Addr, token_id: &str) -> Addr {
+    let code_id = app.store_code(cw721());
+    let nft_contract = app
+        .instantiate_contract(
+            code_id,
+            minter.clone(),
+            &cw721_base::InstantiateMsg {
+                name: "Collection".to_string(),
+                symbol: "COL".to_string(),
+                minter: minter.to_string(),
+            },
+            &[],
+            "Nft contract",
+            None,
+        )
+        .unwrap();
+
+    app.execute_contract(
+        minter.clone(),
+        nft_contract.clone(),
+        &cw721_base::ExecuteMsg::<cw721_base::Extension, Empty>::Mint(cw721_base::MintMsg {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+        }),
+        &[],
+    )
+    .unwrap();
+
+    nft_contract
+}
+
+fn nft_owner(app: &App, nft_contract: &Addr, token_id: &str) -> String {
+    let resp: OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(
+            nft_contract,
+            &Cw721QueryMsg::OwnerOf {
+                token_id: token_id.to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+
+    resp.owner
+}
+
+#[test]
+fn nft_auction() {
+    let seller = Addr::unchecked("seller");
+    let bidder = Addr::unchecked("bidder");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &bidder, coins(100, ATOM))
+            .unwrap();
+    });
+
+    let nft_contract = mint_nft(&mut app, &seller, &seller, "token");
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &seller,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let resp = app
+        .execute_contract(
+            seller.clone(),
+            nft_contract.clone(),
+            &Cw721ExecuteMsg::SendNft {
+                contract: contract.addr().to_string(),
+                token_id: "token".to_string(),
+                msg: to_json_binary(&CreateAuctionMsg {
+                    owner: None,
+                    ..auction_msg(&seller)
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    let auction_id = auction_id(&resp);
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
+    assert_eq!(resp.owner, seller);
+    assert_eq!(
+        resp.nft,
+        Some(Nft {
+            contract: nft_contract.clone(),
+            token_id: "token".to_string(),
+            seller: seller.clone()
+        })
+    );
+    assert_eq!(nft_owner(&app, &nft_contract, "token"), contract.addr().as_str());
+
+    contract.bid(&mut app, &bidder, auction_id, &coins(100, ATOM)).unwrap();
+    contract.close(&mut app, &seller, auction_id).unwrap();
+
+    assert_eq!(nft_owner(&app, &nft_contract, "token"), bidder.as_str());
+    contract
+        .withdraw_commission(&mut app, &seller, auction_id, None, None)
+        .unwrap();
+    assert_eq!(
+        app.wrap().query_all_balances(&seller).unwrap(),
+        coins(100, ATOM)
+    );
+}
+
+#[test]
+fn nft_auction_without_bids() {
+    let seller = Addr::unchecked("seller");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::default();
+
+    let nft_contract = mint_nft(&mut app, &seller, &seller, "token");
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &seller,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    for action in ["close", "cancel"] {
+        let resp = app
+            .execute_contract(
+                seller.clone(),
+                nft_contract.clone(),
+                &Cw721ExecuteMsg::SendNft {
+                    contract: contract.addr().to_string(),
+                    token_id: "token".to_string(),
+                    msg: to_json_binary(&auction_msg(&owner)).unwrap(),
+                },
+                &[],
+            )
+            .unwrap();
+        let auction_id = auction_id(&resp);
+
+        match action {
+            "close" => contract.close(&mut app, &owner, auction_id).unwrap(),
+            _ => contract.cancel(&mut app, &owner, auction_id, None).unwrap(),
+        }
+
+        // The NFT goes back to the seller, not to the owner
+        assert_eq!(nft_owner(&app, &nft_contract, "token"), seller.as_str());
+    }
+}
+
+#[test]
+fn nft_auction_requires_escrow() {
+    let seller = Addr::unchecked("seller");
+
+    let mut app = App::default();
+
+    let nft_contract = mint_nft(&mut app, &seller, &seller, "token");
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &seller,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    // Token never sent to the contract
+    let err = app
+        .execute_contract(
+            nft_contract.clone(),
+            contract.addr().clone(),
+            &ExecMsg::ReceiveNft(Cw721ReceiveMsg {
+                sender: seller.to_string(),
+                token_id: "token".to_string(),
+                msg: to_json_binary(&auction_msg(&seller)).unwrap(),
+            }),
+            &[],
+        )
+        .unwrap_err()
+        .downcast::<ContractError>()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::NftNotEscrowed {
+            token_id: "token".to_string()
+        }
+    );
+
+    // Not an NFT contract at all
+    app.execute_contract(
+        seller.clone(),
+        contract.addr().clone(),
+        &ExecMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: seller.to_string(),
+            token_id: "token".to_string(),
+            msg: to_json_binary(&auction_msg(&seller)).unwrap(),
+        }),
+        &[],
+    )
+    .unwrap_err();
+}
+
+fn cw20_balance(app: &App, cw20_contract: &Addr, address: &Addr) -> Uint128 {
+    let resp: BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(
+            cw20_contract,
+            &Cw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+
+    resp.balance
+}
+
+#[test]
+fn cw20_auction() {
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::default();
+
+    let cw20_id = app.store_code(cw20());
+    let cw20_contract = app
+        .instantiate_contract(
+            cw20_id,
+            owner.clone(),
+            &cw20_base::msg::InstantiateMsg {
+                name: "Token".to_string(),
+                symbol: "TKN".to_string(),
+                decimals: 6,
+                initial_balances: vec![
+                    Cw20Coin {
+                        address: sender1.to_string(),
+                        amount: Uint128::new(20),
+                    },
+                    Cw20Coin {
+                        address: sender2.to_string(),
+                        amount: Uint128::new(10),
+                    },
+                ],
+                mint: None,
+                marketing: None,
+            },
+            &[],
+            "Token",
+            None,
+        )
+        .unwrap();
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction(
+            &mut app,
+            &owner,
+            None,
+            Decimal::percent(10),
+            Denom::Cw20(cw20_contract.clone()),
+        )
+        .unwrap();
+
+    for (sender, amount) in [(&sender2, 10), (&sender1, 20)] {
+        app.execute_contract(
+            sender.clone(),
+            cw20_contract.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: contract.addr().to_string(),
+                amount: Uint128::new(amount),
+                msg: to_json_binary(&ReceiveMsg::Bid { auction_id }).unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
+    assert_eq!(resp.token, Denom::Cw20(cw20_contract.clone()));
+    assert_eq!(
+        resp.highest_bid,
+        Bid {
+            addr: sender1.clone(),
+            amount: Uint128::new(18)
+        }
+    );
+    contract
+        .withdraw_commission(&mut app, &owner, auction_id, None, None)
+        .unwrap();
+    assert_eq!(cw20_balance(&app, &cw20_contract, &owner), Uint128::new(3));
+    assert_eq!(
+        cw20_balance(&app, &cw20_contract, contract.addr()),
+        Uint128::new(27)
+    );
+
+    let err = contract
+        .bid(&mut app, &sender2, auction_id, &[])
+        .unwrap_err();
+    assert_eq!(err, ContractError::NoFunds);
+
+    contract.close(&mut app, &owner, auction_id).unwrap();
+    contract.retract(&mut app, &sender2, auction_id, None).unwrap();
+
+    assert_eq!(cw20_balance(&app, &cw20_contract, &owner), Uint128::new(21));
+    assert_eq!(cw20_balance(&app, &cw20_contract, &sender2), Uint128::new(9));
+    assert_eq!(
+        cw20_balance(&app, &cw20_contract, contract.addr()),
+        Uint128::zero()
+    );
+}
+
+#[test]
+fn min_increment() {
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(100, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(215, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                min_increment: Some(Uint128::new(5)),
+                min_increment_percent: Some(Decimal::percent(10)),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
+    assert_eq!(resp.min_increment, Uint128::new(5));
+    assert_eq!(resp.min_increment_percent, Decimal::percent(10));
+
+    contract.bid(&mut app, &sender1, auction_id, &coins(100, ATOM)).unwrap();
+
+    let err = contract
+        .bid(&mut app, &sender2, auction_id, &coins(105, ATOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::BidBelowMinimum {
+            minimum: Uint128::new(99)
+        }
+    );
+
+    contract.bid(&mut app, &sender2, auction_id, &coins(110, ATOM)).unwrap();
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
+    assert_eq!(
+        resp.highest_bid,
+        Bid {
+            addr: sender2,
+            amount: Uint128::new(99)
+        }
+    );
+}
+
+#[test]
+fn reserve_not_met() {
+    let sender = Addr::unchecked("sender");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                reserve: Some(Reserve::Public(Uint128::new(50))),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    contract.bid(&mut app, &sender, auction_id, &coins(20, ATOM)).unwrap();
+    contract.close(&mut app, &owner, auction_id).unwrap();
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
+    assert!(!resp.open);
+    assert_eq!(resp.status, AuctionStatus::Unsold);
+    assert_eq!(
+        contract.query_bids(&app, auction_id, None, None).unwrap().bids,
+        vec![Bid {
+            addr: sender.clone(),
+            amount: Uint128::new(18)
+        }]
+    );
+    contract
+        .withdraw_commission(&mut app, &owner, auction_id, None, None)
+        .unwrap();
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        coins(2, ATOM)
+    );
+
+    contract.retract(&mut app, &sender, auction_id, None).unwrap();
+
+    assert_eq!(
+        app.wrap().query_all_balances(&sender).unwrap(),
+        coins(18, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(contract.addr()).unwrap(),
+        vec![]
+    );
+}
+
+#[test]
+fn hidden_reserve() {
+    let sender = Addr::unchecked("sender");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(40, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let msg = CreateAuctionMsg {
+        reserve: Some(Reserve::Hidden(commitment(Uint128::new(15), "salt"))),
+        ..auction_msg(&owner)
+    };
+    let revealed = contract
+        .create_auction_with_msg(&mut app, &owner, msg.clone())
+        .unwrap();
+    let unrevealed = contract
+        .create_auction_with_msg(&mut app, &owner, msg)
+        .unwrap();
+
+    contract.bid(&mut app, &sender, revealed, &coins(20, ATOM)).unwrap();
+    contract.bid(&mut app, &sender, unrevealed, &coins(20, ATOM)).unwrap();
+
+    let err = contract
+        .reveal_reserve(&mut app, &owner, revealed, Uint128::new(15), "pepper")
+        .unwrap_err();
+    assert_eq!(err, ContractError::ReserveMismatch);
+
+    contract
+        .reveal_reserve(&mut app, &owner, revealed, Uint128::new(15), "salt")
+        .unwrap();
+
+    let resp = contract.query_value(&app, revealed).unwrap();
+    assert_eq!(resp.reserve, Some(Reserve::Public(Uint128::new(15))));
+
+    let err = contract
+        .reveal_reserve(&mut app, &owner, revealed, Uint128::new(15), "salt")
+        .unwrap_err();
+    assert_eq!(err, ContractError::ReserveNotHidden);
+
+    contract.close(&mut app, &owner, revealed).unwrap();
+    contract.close(&mut app, &owner, unrevealed).unwrap();
+
+    let resp = contract.query_value(&app, revealed).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Sold);
+
+    let resp = contract.query_value(&app, unrevealed).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Unsold);
+
+    contract
+        .withdraw_commission(&mut app, &owner, revealed, None, None)
+        .unwrap();
+    contract
+        .withdraw_commission(&mut app, &owner, unrevealed, None, None)
+        .unwrap();
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        coins(22, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(contract.addr()).unwrap(),
+        coins(18, ATOM)
+    );
+}
+
+#[test]
+fn buy_now() {
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(40, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(70, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    for (buy_now_price, reserve) in [(0, None), (50, Some(Reserve::Public(Uint128::new(60))))] {
+        let err = contract
+            .create_auction_with_msg(
+                &mut app,
+                &owner,
+                CreateAuctionMsg {
+                    buy_now_price: Some(Uint128::new(buy_now_price)),
+                    reserve,
+                    ..auction_msg(&owner)
+                },
+            )
+            .unwrap_err();
+        assert_eq!(err, ContractError::InvalidBuyNowPrice);
+    }
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                buy_now_price: Some(Uint128::new(50)),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    contract.bid(&mut app, &sender1, auction_id, &coins(20, ATOM)).unwrap();
+    contract.bid(&mut app, &sender2, auction_id, &coins(70, ATOM)).unwrap();
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Sold);
+    assert_eq!(resp.buy_now_price, Some(Uint128::new(50)));
+    // Totals are net of the commission charged on the bid
+    assert_eq!(
+        resp.highest_bid,
+        Bid {
+            addr: sender2.clone(),
+            amount: Uint128::new(45)
+        }
+    );
+    let resp = contract
+        .query_bid_history(&app, auction_id, Some(&sender2), None, None)
+        .unwrap();
+    let summary: Vec<_> = resp
+        .bids
+        .iter()
+        .map(|bid| (bid.increment.u128(), bid.total.u128()))
+        .collect();
+    assert_eq!(summary, vec![(45, 45)]);
+    assert_eq!(
+        contract.query_bids(&app, auction_id, None, None).unwrap().bids,
+        vec![Bid {
+            addr: sender1.clone(),
+            amount: Uint128::new(18)
+        }]
+    );
+    contract
+        .withdraw_commission(&mut app, &owner, auction_id, None, None)
+        .unwrap();
+    // Commission is only charged on the part of the bid reaching the price
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        coins(52, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(&sender2).unwrap(),
+        coins(20, ATOM)
+    );
+
+    let err = contract
+        .bid(&mut app, &sender1, auction_id, &coins(20, ATOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::BiddingClosed);
+
+    contract.retract(&mut app, &sender1, auction_id, None).unwrap();
+
+    assert_eq!(
+        app.wrap().query_all_balances(&sender1).unwrap(),
+        coins(38, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(contract.addr()).unwrap(),
+        vec![]
+    );
+}
+
+#[test]
+fn buy_now_other_denoms() {
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, vec![coin(50, ATOM), coin(50, "ujunk")])
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let cw20_id = app.store_code(cw20());
+    let cw20_contract = app
+        .instantiate_contract(
+            cw20_id,
+            owner.clone(),
+            &cw20_base::msg::InstantiateMsg {
+                name: "Token".to_string(),
+                symbol: "TKN".to_string(),
+                decimals: 6,
+                initial_balances: vec![Cw20Coin {
+                    address: sender1.to_string(),
+                    amount: Uint128::new(50),
+                }],
+                mint: None,
+                marketing: None,
+            },
+            &[],
+            "Token",
+            None,
+        )
+        .unwrap();
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                buy_now_price: Some(Uint128::new(50)),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    contract.bid(&mut app, &sender2, auction_id, &coins(20, ATOM)).unwrap();
+
+    let err = contract
+        .bid(&mut app, &sender1, auction_id, &coins(50, "ujunk"))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidToken {
+            token: "ujunk".to_string()
+        }
+    );
+    assert_eq!(
+        contract.query_value(&app, auction_id).unwrap().status,
+        AuctionStatus::Open
+    );
+
+    let cw20_auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                token: Denom::Cw20(cw20_contract.clone()),
+                buy_now_price: Some(Uint128::new(50)),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    let err = contract
+        .bid(&mut app, &sender1, cw20_auction_id, &coins(50, ATOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidToken {
+            token: ATOM.to_string()
+        }
+    );
+    assert_eq!(
+        contract.query_value(&app, cw20_auction_id).unwrap().status,
+        AuctionStatus::Open
+    );
+
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        vec![]
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(contract.addr()).unwrap(),
+        coins(20, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(&sender1).unwrap(),
+        vec![coin(50, ATOM), coin(50, "ujunk")]
+    );
+}
+
+#[test]
+fn dutch_auction() {
+    let buyer = Addr::unchecked("buyer");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &buyer, coins(70, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                mode: Some(AuctionMode::Dutch {
+                    start_price: Uint128::new(100),
+                    floor_price: Uint128::new(20),
+                    decay: PriceDecay::Linear { duration: 100 },
+                }),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    let resp = contract.query_current_price(&app, auction_id).unwrap();
+    assert_eq!(resp.price, Some(Uint128::new(100)));
+
+    let err = contract
+        .bid(&mut app, &buyer, auction_id, &coins(70, ATOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidAuctionMode);
+
+    app.update_block(|block| block.time = block.time.plus_seconds(25));
+
+    let err = contract
+        .buy(&mut app, &buyer, auction_id, &coins(70, ATOM))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::PriceNotMet {
+            price: Uint128::new(80)
+        }
+    );
+
+    app.update_block(|block| block.time = block.time.plus_seconds(25));
+
+    let resp = contract.query_current_price(&app, auction_id).unwrap();
+    assert_eq!(resp.price, Some(Uint128::new(60)));
+
+    contract.buy(&mut app, &buyer, auction_id, &coins(70, ATOM)).unwrap();
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Sold);
+    assert_eq!(
+        resp.highest_bid,
+        Bid {
+            addr: buyer.clone(),
+            amount: Uint128::new(54)
+        }
+    );
+    let resp = contract
+        .query_bid_history(&app, auction_id, None, None, None)
+        .unwrap();
+    let summary: Vec<_> = resp
+        .bids
+        .iter()
+        .map(|bid| (bid.increment.u128(), bid.total.u128()))
+        .collect();
+    assert_eq!(summary, vec![(54, 54)]);
+    contract
+        .withdraw_commission(&mut app, &owner, auction_id, None, None)
+        .unwrap();
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        coins(60, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(&buyer).unwrap(),
+        coins(10, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(contract.addr()).unwrap(),
+        vec![]
+    );
+}
+
+#[test]
+fn dutch_auction_stepwise_price() {
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::default();
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                mode: Some(AuctionMode::Dutch {
+                    start_price: Uint128::new(100),
+                    floor_price: Uint128::new(50),
+                    decay: PriceDecay::Stepwise {
+                        interval: 10,
+                        step: Uint128::new(20),
+                    },
+                }),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    app.update_block(|block| block.time = block.time.plus_seconds(25));
+
+    let resp = contract.query_current_price(&app, auction_id).unwrap();
+    assert_eq!(resp.price, Some(Uint128::new(60)));
+
+    app.update_block(|block| block.time = block.time.plus_seconds(1000));
+
+    let resp = contract.query_current_price(&app, auction_id).unwrap();
+    assert_eq!(resp.price, Some(Uint128::new(50)));
+}
+
+#[test]
+fn sealed_auction() {
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let sender3 = Addr::unchecked("sender3");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(60, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(40, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender3, coins(30, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let commit_end = app.block_info().time.plus_seconds(100);
+    let reveal_end = commit_end.plus_seconds(100);
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                mode: Some(AuctionMode::Sealed {
+                    commit_end,
+                    reveal_end,
+                    refund_unrevealed: false,
+                }),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    let err = contract
+        .bid(&mut app, &sender1, auction_id, &coins(60, ATOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidAuctionMode);
+
+    contract
+        .commit(
+            &mut app,
+            &sender1,
+            auction_id,
+            bid_commitment(auction_id, &sender1, Uint128::new(50), "salt1"),
+            &coins(60, ATOM),
+        )
+        .unwrap();
+    contract
+        .commit(
+            &mut app,
+            &sender2,
+            auction_id,
+            bid_commitment(auction_id, &sender2, Uint128::new(40), "salt2"),
+            &coins(40, ATOM),
+        )
+        .unwrap();
+    contract
+        .commit(
+            &mut app,
+            &sender3,
+            auction_id,
+            bid_commitment(auction_id, &sender3, Uint128::new(30), "salt3"),
+            &coins(30, ATOM),
+        )
+        .unwrap();
+
+    // Copying someone else's commitment doesn't let a bidder reveal it
+    contract
+        .commit(
+            &mut app,
+            &sender3,
+            auction_id,
+            bid_commitment(auction_id, &sender1, Uint128::new(50), "salt1"),
+            &[],
+        )
+        .unwrap();
+
+    let err = contract
+        .reveal(&mut app, &sender1, auction_id, Uint128::new(50), "salt1")
+        .unwrap_err();
+    assert_eq!(err, ContractError::NotRevealPhase);
+
+    app.update_block(|block| block.time = commit_end);
+
+    let err = contract
+        .commit(
+            &mut app,
+            &sender1,
+            auction_id,
+            bid_commitment(auction_id, &sender1, Uint128::new(50), "salt1"),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::NotCommitPhase);
+
+    let err = contract
+        .reveal(&mut app, &sender1, auction_id, Uint128::new(50), "salt2")
+        .unwrap_err();
+    assert_eq!(err, ContractError::CommitmentMismatch);
+
+    let err = contract
+        .reveal(&mut app, &sender3, auction_id, Uint128::new(50), "salt1")
+        .unwrap_err();
+    assert_eq!(err, ContractError::CommitmentMismatch);
+
+    // Pausing can't keep bidders from revealing and forfeit their deposits
+    contract.pause(&mut app, &owner, auction_id, None).unwrap();
+
+    contract
+        .reveal(&mut app, &sender1, auction_id, Uint128::new(50), "salt1")
+        .unwrap();
+    contract
+        .reveal(&mut app, &sender2, auction_id, Uint128::new(40), "salt2")
+        .unwrap();
+
+    contract.unpause(&mut app, &owner, auction_id).unwrap();
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
+    assert_eq!(
+        resp.highest_bid,
+        Bid {
+            addr: sender1.clone(),
+            amount: Uint128::new(45)
+        }
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(&sender1).unwrap(),
+        coins(10, ATOM)
+    );
+
+    let err = contract.close(&mut app, &owner, auction_id).unwrap_err();
+    assert_eq!(err, ContractError::EarlyCloseErr);
+
+    app.update_block(|block| block.time = reveal_end);
+
+    contract.close(&mut app, &owner, auction_id).unwrap();
+    contract.retract(&mut app, &sender2, auction_id, None).unwrap();
+
+    let err = contract
+        .retract(&mut app, &sender3, auction_id, None)
+        .unwrap_err();
+    assert_eq!(err, ContractError::NoBidsRetractErr);
+
+    contract
+        .withdraw_commission(&mut app, &owner, auction_id, None, None)
+        .unwrap();
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        coins(84, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(&sender2).unwrap(),
+        coins(36, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(contract.addr()).unwrap(),
+        vec![]
+    );
+}
+
+#[test]
+fn sealed_auction_refunds_unrevealed() {
+    let sender = Addr::unchecked("sender");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(30, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let commit_end = app.block_info().time.plus_seconds(100);
+    let reveal_end = commit_end.plus_seconds(100);
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                mode: Some(AuctionMode::Sealed {
+                    commit_end,
+                    reveal_end,
+                    refund_unrevealed: true,
+                }),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    contract
+        .commit(
+            &mut app,
+            &sender,
+            auction_id,
+            bid_commitment(auction_id, &sender, Uint128::new(30), "salt"),
+            &coins(30, ATOM),
+        )
+        .unwrap();
+
+    app.update_block(|block| block.time = reveal_end);
+
+    contract.close(&mut app, &owner, auction_id).unwrap();
+    contract.retract(&mut app, &sender, auction_id, None).unwrap();
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Unsold);
+    assert_eq!(
+        app.wrap().query_all_balances(&sender).unwrap(),
+        coins(30, ATOM)
+    );
+    assert_eq!(app.wrap().query_all_balances(&owner).unwrap(), vec![]);
+}
+
+#[test]
+fn second_price_settlement() {
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(20, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(40, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                settlement: Some(Settlement::SecondPrice {
+                    increment: Some(Uint128::new(1)),
+                }),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    contract.bid(&mut app, &sender1, auction_id, &coins(20, ATOM)).unwrap();
+    contract.bid(&mut app, &sender2, auction_id, &coins(30, ATOM)).unwrap();
+    contract.bid(&mut app, &sender2, auction_id, &coins(10, ATOM)).unwrap();
+
+    contract.close(&mut app, &owner, auction_id).unwrap();
+    contract.retract(&mut app, &sender1, auction_id, None).unwrap();
+
+    contract
+        .withdraw_commission(&mut app, &owner, auction_id, None, None)
+        .unwrap();
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        coins(25, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(&sender1).unwrap(),
+        coins(18, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(&sender2).unwrap(),
+        coins(17, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(contract.addr()).unwrap(),
+        vec![]
+    );
+}
+
+#[test]
+fn soft_close() {
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(30, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(30, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let start = app.block_info().time;
+    let end_time = start.plus_seconds(100);
+    let max_end_time = start.plus_seconds(115);
+
+    let invalid = [
+        (Some(start.plus_seconds(90)), None, ContractError::MaxEndBeforeEnd),
+        (
+            Some(max_end_time),
+            Some(start.plus_seconds(110)),
+            ContractError::SettlementBeforeEnd,
+        ),
+        (None, Some(start.plus_seconds(200)), ContractError::SettlementBeforeEnd),
+    ];
+    for (max_end_time, settlement_deadline, expected) in invalid {
+        let err = contract
+            .create_auction_with_msg(
+                &mut app,
+                &owner,
+                CreateAuctionMsg {
+                    end_time: Some(end_time),
+                    extension_window: Some(10),
+                    extension_duration: Some(20),
+                    max_end_time,
+                    settlement_deadline,
+                    ..auction_msg(&owner)
+                },
+            )
+            .unwrap_err();
+        assert_eq!(err, expected);
+    }
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                end_time: Some(end_time),
+                extension_window: Some(10),
+                extension_duration: Some(20),
+                max_end_time: Some(max_end_time),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    app.update_block(|block| block.time = start.plus_seconds(50));
+    contract.bid(&mut app, &sender1, auction_id, &coins(10, ATOM)).unwrap();
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
+    assert_eq!(resp.end_time, Some(end_time));
+
+    app.update_block(|block| block.time = start.plus_seconds(95));
+    contract.bid(&mut app, &sender2, auction_id, &coins(20, ATOM)).unwrap();
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
+    assert_eq!(resp.end_time, Some(max_end_time));
+
+    app.update_block(|block| block.time = start.plus_seconds(110));
+    contract.bid(&mut app, &sender1, auction_id, &coins(20, ATOM)).unwrap();
+
+    let resp = contract.query_value(&app, auction_id).unwrap();
+    assert_eq!(resp.end_time, Some(max_end_time));
+
+    app.update_block(|block| block.time = max_end_time);
+    let err = contract
+        .bid(&mut app, &sender2, auction_id, &coins(10, ATOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::BiddingEnded);
+}
+
+#[test]
+fn migrate_from_single_auction() {
+    let sender1 = Addr::unchecked("sender1");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let legacy_id = app.store_code(legacy_platform());
+    let contract_id = app.store_code(bidding_platform());
+
+    let addr = app
+        .instantiate_contract(
+            legacy_id,
+            owner.clone(),
+            &Empty {},
+            &[],
+            "Bidding contract",
+            Some(owner.to_string()),
+        )
+        .unwrap();
+    let contract = BiddingPlatform(addr);
+
+    contract.migrate(&mut app, &owner, contract_id).unwrap();
+
+    let resp = contract.query_value(&app, 0).unwrap();
+    assert!(resp.open);
+    assert_eq!(resp.token, Denom::Native(ATOM.to_owned()));
+    assert_eq!(resp.owner, owner);
+    assert_eq!(resp.part, Decimal::percent(10));
+    assert_eq!(
+        resp.highest_bid,
+        Bid {
+            addr: sender1.clone(),
+            amount: Uint128::new(18)
+        }
+    );
+    assert_eq!(
+        contract.query_bids(&app, 0, None, None).unwrap().bids,
+        vec![Bid {
+            addr: sender1.clone(),
+            amount: Uint128::new(18)
+        }]
+    );
+
+    // New auctions are created after the migrated one
+    let auction_id = contract
+        .create_auction(&mut app, &owner, None, Decimal::percent(10), Denom::Native(ATOM.to_owned()))
+        .unwrap();
+    assert_eq!(auction_id, 1);
+
+    // Migrating again to the same version is a no-op
+    contract.migrate(&mut app, &owner, contract_id).unwrap();
+    assert_eq!(contract.query_value(&app, 0).unwrap().highest_bid.amount, Uint128::new(18));
+}
+
+#[test]
+fn migrate_refuses_downgrade() {
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::default();
+
+    let future_id = app.store_code(stub_platform(|deps, _env, _info, _msg| {
+        set_contract_version(deps.storage, env!("CARGO_PKG_NAME"), "9.0.0")?;
+        Ok(Response::new())
+    }));
+    let contract_id = app.store_code(bidding_platform());
+
+    let addr = app
+        .instantiate_contract(
+            future_id,
+            owner.clone(),
+            &Empty {},
+            &[],
+            "Bidding contract",
+            Some(owner.to_string()),
+        )
+        .unwrap();
+    let contract = BiddingPlatform(addr);
+
+    let err = contract.migrate(&mut app, &owner, contract_id).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Downgrade {
+            stored: "9.0.0".to_owned(),
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+        }
+    );
+}
+
+#[test]
+fn paginated_bids() {
+    let owner = Addr::unchecked("owner");
+    let bidders: Vec<_> = (0..32)
+        .map(|idx| Addr::unchecked(format!("bidder{:02}", idx)))
+        .collect();
+
+    let mut app = App::new(|router, _api, storage| {
+        for bidder in &bidders {
+            router
+                .bank
+                .init_balance(storage, bidder, coins(1000, ATOM))
+                .unwrap();
+        }
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction(&mut app, &owner, None, Decimal::percent(10), Denom::Native(ATOM.to_string()))
+        .unwrap();
+
+    for (idx, bidder) in bidders.iter().enumerate() {
+        let amount = 10 * (idx as u128 + 1);
+        contract
+            .bid(&mut app, bidder, auction_id, &coins(amount, ATOM))
+            .unwrap();
+    }
+
+    let expected: Vec<_> = bidders
+        .iter()
+        .enumerate()
+        .map(|(idx, bidder)| Bid {
+            addr: bidder.clone(),
+            amount: Uint128::new(9 * (idx as u128 + 1)),
+        })
+        .collect();
+
+    let resp = contract.query_bids(&app, auction_id, None, None).unwrap();
+    assert_eq!(resp.bids, expected[..10]);
+
+    let resp = contract
+        .query_bids(&app, auction_id, Some(&bidders[9]), Some(5))
+        .unwrap();
+    assert_eq!(resp.bids, expected[10..15]);
+
+    let resp = contract
+        .query_bids(&app, auction_id, None, Some(100))
+        .unwrap();
+    assert_eq!(resp.bids, expected[..30]);
+
+    let resp = contract
+        .query_bids(&app, auction_id, Some(&bidders[29]), Some(100))
+        .unwrap();
+    assert_eq!(resp.bids, expected[30..]);
+}
+
+#[test]
+fn queries_at_height() {
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(10, ATOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction(&mut app, &owner, None, Decimal::percent(10), Denom::Native(ATOM.to_string()))
+        .unwrap();
+
+    let start = app.block_info().height;
+    app.update_block(|block| block.height += 1);
+    contract
+        .bid(&mut app, &sender1, auction_id, &coins(10, ATOM))
+        .unwrap();
+    app.update_block(|block| block.height += 1);
+    contract
+        .bid(&mut app, &sender2, auction_id, &coins(20, ATOM))
+        .unwrap();
+    app.update_block(|block| block.height += 1);
+    contract.close(&mut app, &owner, auction_id).unwrap();
+
+    // Bids show up from the height of the block they were placed in
+    let highest: Vec<_> = (start..start + 4)
+        .map(|height| {
+            contract
+                .query_highest_bid_at_height(&app, auction_id, height)
+                .unwrap()
+                .bid
+                .map(|bid| (bid.addr, bid.amount.u128()))
+        })
+        .collect();
+    assert_eq!(
+        highest,
+        vec![
+            None,
+            Some((sender1.clone(), 9)),
+            Some((sender2.clone(), 18)),
+            Some((sender2.clone(), 18)),
+        ]
+    );
+
+    let totals: Vec<_> = (start..start + 5)
+        .map(|height| {
+            let of = |addr| {
+                contract
+                    .query_bid_of_at_height(&app, auction_id, addr, height)
+                    .unwrap()
+                    .amount
+                    .map(|amount| amount.u128())
+            };
+            (of(&sender1), of(&sender2))
+        })
+        .collect();
+    assert_eq!(
+        totals,
+        vec![
+            (None, None),
+            (Some(9), None),
+            (Some(9), Some(18)),
+            // The winner's total is settled on close.
+            (Some(9), None),
+            (Some(9), None),
+        ]
+    );
+}
+
+#[test]
+fn top_bids() {
+    let owner = Addr::unchecked("owner");
+    let bidders: Vec<_> = (0..12)
+        .map(|idx| Addr::unchecked(format!("bidder{:02}", idx)))
+        .collect();
+
+    let mut app = App::new(|router, _api, storage| {
+        for bidder in &bidders {
+            router
+                .bank
+                .init_balance(storage, bidder, coins(1000, ATOM))
+                .unwrap();
+        }
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction(&mut app, &owner, None, Decimal::percent(10), Denom::Native(ATOM.to_string()))
+        .unwrap();
+
+    for (idx, bidder) in bidders.iter().enumerate() {
+        let amount = 10 * (idx as u128 + 1);
+        contract
+            .bid(&mut app, bidder, auction_id, &coins(amount, ATOM))
+            .unwrap();
+    }
+
+    // Raising a bid moves the bidder to the top.
+    contract
+        .bid(&mut app, &bidders[0], auction_id, &coins(200, ATOM))
+        .unwrap();
+
+    let top = contract.query_top_bids(&app, auction_id, None, Some(3)).unwrap();
+    assert_eq!(
+        top.bids,
+        vec![
+            Bid {
+                addr: bidders[0].clone(),
+                amount: Uint128::new(189),
+            },
+            Bid {
+                addr: bidders[11].clone(),
+                amount: Uint128::new(108),
+            },
+            Bid {
+                addr: bidders[10].clone(),
+                amount: Uint128::new(99),
+            },
+        ]
+    );
+
+    let resp = contract
+        .query_top_bids(&app, auction_id, top.bids.last(), Some(2))
+        .unwrap();
+    let addrs: Vec<_> = resp.bids.into_iter().map(|bid| bid.addr).collect();
+    assert_eq!(addrs, vec![bidders[9].clone(), bidders[8].clone()]);
+
+    let cursor = Bid {
+        addr: bidders[2].clone(),
+        amount: Uint128::new(27),
+    };
+    let resp = contract
+        .query_top_bids(&app, auction_id, Some(&cursor), None)
+        .unwrap();
+    let addrs: Vec<_> = resp.bids.into_iter().map(|bid| bid.addr).collect();
+    assert_eq!(addrs, vec![bidders[1].clone()]);
+
+    contract.close(&mut app, &owner, auction_id).unwrap();
+
+    // The winner is paid out and no longer listed, but still works as a cursor
+    let resp = contract.query_top_bids(&app, auction_id, None, Some(1)).unwrap();
+    assert_eq!(resp.bids[0].addr, bidders[11]);
+
+    let resp = contract
+        .query_top_bids(&app, auction_id, top.bids.first(), Some(1))
+        .unwrap();
+    assert_eq!(resp.bids[0].addr, bidders[11]);
+}
+
+#[test]
+fn bid_history() {
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(30, ATOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction(&mut app, &owner, None, Decimal::percent(10), Denom::Native(ATOM.to_string()))
+        .unwrap();
+
+    let start = app.block_info();
+    contract
+        .bid(&mut app, &sender1, auction_id, &coins(10, ATOM))
+        .unwrap();
+    app.update_block(|block| {
+        block.height += 1;
+        block.time = block.time.plus_seconds(5);
+    });
+    contract
+        .bid(&mut app, &sender2, auction_id, &coins(20, ATOM))
+        .unwrap();
+    app.update_block(|block| {
+        block.height += 1;
+        block.time = block.time.plus_seconds(5);
+    });
+    contract
+        .bid(&mut app, &sender1, auction_id, &coins(20, ATOM))
+        .unwrap();
+
+    let resp = contract
+        .query_bid_history(&app, auction_id, None, None, None)
+        .unwrap();
+    let summary: Vec<_> = resp
+        .bids
+        .iter()
+        .map(|bid| (bid.id, bid.bidder.clone(), bid.increment.u128(), bid.total.u128()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (0, sender1.clone(), 9, 9),
+            (1, sender2.clone(), 18, 18),
+            (2, sender1.clone(), 18, 27),
+        ]
+    );
+    assert_eq!(resp.bids[0].height, start.height);
+    assert_eq!(resp.bids[0].time, start.time);
+    assert_eq!(resp.bids[2].height, start.height + 2);
+    assert_eq!(resp.bids[2].time, start.time.plus_seconds(10));
+
+    let resp = contract
+        .query_bid_history(&app, auction_id, Some(&sender1), None, None)
+        .unwrap();
+    let ids: Vec<_> = resp.bids.iter().map(|bid| bid.id).collect();
+    assert_eq!(ids, vec![0, 2]);
+
+    let resp = contract
+        .query_bid_history(&app, auction_id, None, Some(0), Some(1))
+        .unwrap();
+    let ids: Vec<_> = resp.bids.iter().map(|bid| bid.id).collect();
+    assert_eq!(ids, vec![1]);
+
+    let resp = contract
+        .query_bid_history(&app, auction_id, Some(&sender1), Some(0), None)
+        .unwrap();
+    let ids: Vec<_> = resp.bids.iter().map(|bid| bid.id).collect();
+    assert_eq!(ids, vec![2]);
+
+    let resp = contract
+        .query_bid_history(&app, auction_id, Some(&sender2), Some(1), None)
+        .unwrap();
+    assert_eq!(resp.bids, vec![]);
+}
+
+#[test]
+fn focused_queries() {
+    let sender1 = Addr::unchecked("sender1");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let end_time = app.block_info().time.plus_seconds(100);
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                end_time: Some(end_time),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    let resp = contract.query_config(&app, auction_id).unwrap();
+    assert_eq!(
+        resp,
+        ConfigResponse {
+            owner: owner.clone(),
+            owner_renounced: false,
+            token: Denom::Native(ATOM.to_string()),
+            part: Decimal::percent(10),
+        }
+    );
+
+    let resp = contract.query_status(&app, auction_id).unwrap();
+    assert_eq!(
+        resp,
+        StatusResponse {
+            status: AuctionStatus::Open,
+            ended: false,
+            end_time: Some(end_time),
+            end_height: None,
+            settlement_deadline: None,
+            paused: false,
+            pause_reason: None,
+        }
+    );
+
+    let resp = contract.query_highest_bid(&app, auction_id).unwrap();
+    assert_eq!(resp.bid, None);
+
+    let resp = contract.query_bid_of(&app, auction_id, &sender1).unwrap();
+    assert_eq!(resp.amount, None);
+
+    contract
+        .bid(&mut app, &sender1, auction_id, &coins(20, ATOM))
+        .unwrap();
+
+    let resp = contract.query_highest_bid(&app, auction_id).unwrap();
+    assert_eq!(
+        resp.bid,
+        Some(Bid {
+            addr: sender1.clone(),
+            amount: Uint128::new(18),
+        })
+    );
+
+    let resp = contract.query_bid_of(&app, auction_id, &sender1).unwrap();
+    assert_eq!(resp.amount, Some(Uint128::new(18)));
+
+    app.update_block(|block| block.time = end_time);
+
+    let resp = contract.query_status(&app, auction_id).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Open);
+    assert!(resp.ended);
+
+    contract.close(&mut app, &owner, auction_id).unwrap();
+
+    let resp = contract.query_status(&app, auction_id).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Sold);
+}
+
+#[test]
+fn withdraw_commission() {
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let owner = Addr::unchecked("owner");
+    let collector = Addr::unchecked("collector");
+    let treasury = Addr::unchecked("treasury");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(10, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(100, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                part: Decimal::percent(5),
+                fee_collector: Some(collector.to_string()),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    // Commission rounding down to zero doesn't fail the bid
+    contract.bid(&mut app, &sender1, auction_id, &coins(10, ATOM)).unwrap();
+    assert_eq!(
+        contract.query_accrued_commission(&app, auction_id).unwrap().amount,
+        Uint128::zero()
+    );
+
+    let err = contract
+        .withdraw_commission(&mut app, &owner, auction_id, None, None)
+        .unwrap_err();
+    assert_eq!(err, ContractError::NoCommission);
+
+    contract.bid(&mut app, &sender2, auction_id, &coins(100, ATOM)).unwrap();
+    assert_eq!(
+        contract.query_accrued_commission(&app, auction_id).unwrap().amount,
+        Uint128::new(5)
+    );
+
+    let err = contract
+        .withdraw_commission(&mut app, &sender1, auction_id, None, None)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized {
+            owner: owner.to_string()
+        }
+    );
+
+    let err = contract
+        .withdraw_commission(&mut app, &collector, auction_id, Some(6), None)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientCommission {
+            accrued: Uint128::new(5)
+        }
+    );
+
+    contract
+        .withdraw_commission(&mut app, &collector, auction_id, Some(2), Some(&treasury))
+        .unwrap();
+    contract
+        .withdraw_commission(&mut app, &owner, auction_id, None, None)
+        .unwrap();
+
+    assert_eq!(
+        contract.query_accrued_commission(&app, auction_id).unwrap().amount,
+        Uint128::zero()
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(&treasury).unwrap(),
+        coins(2, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        coins(3, ATOM)
+    );
+}
+
+#[test]
+fn commission_on_settlement() {
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(50, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(100, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let err = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                part: Decimal::percent(110),
+                commission_mode: Some(CommissionMode::OnSettlement),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::PartTooHigh);
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                commission_mode: Some(CommissionMode::OnSettlement),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    contract.bid(&mut app, &sender1, auction_id, &coins(50, ATOM)).unwrap();
+    contract.bid(&mut app, &sender2, auction_id, &coins(100, ATOM)).unwrap();
+
+    assert_eq!(
+        contract.query_bids(&app, auction_id, None, None).unwrap().bids,
+        vec![
+            Bid {
+                addr: sender1.clone(),
+                amount: Uint128::new(50)
+            },
+            Bid {
+                addr: sender2.clone(),
+                amount: Uint128::new(100)
+            }
+        ]
+    );
+    assert_eq!(
+        contract.query_accrued_commission(&app, auction_id).unwrap().amount,
+        Uint128::zero()
+    );
+
+    contract.close(&mut app, &owner, auction_id).unwrap();
+    contract.retract(&mut app, &sender1, auction_id, None).unwrap();
+
+    assert_eq!(
+        app.wrap().query_all_balances(&sender1).unwrap(),
+        coins(50, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        coins(90, ATOM)
+    );
+    assert_eq!(
+        contract.query_accrued_commission(&app, auction_id).unwrap().amount,
+        Uint128::new(10)
+    );
+}
+
+#[test]
+fn transfer_ownership() {
+    let sender = Addr::unchecked("sender");
+    let owner = Addr::unchecked("owner");
+    let multisig = Addr::unchecked("multisig");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(100, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction(&mut app, &owner, None, Decimal::percent(10), Denom::Native(ATOM.to_string()))
+        .unwrap();
+
+    let expiry = Expiration::AtHeight(app.block_info().height + 10);
+
+    let err = contract
+        .transfer_ownership(&mut app, &sender, auction_id, &sender, None)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized {
+            owner: owner.to_string()
+        }
+    );
+
+    contract
+        .transfer_ownership(&mut app, &owner, auction_id, &multisig, Some(expiry))
+        .unwrap();
+
+    let resp = contract.query_pending_owner(&app, auction_id).unwrap();
+    assert_eq!(
+        resp,
+        PendingOwnerResponse {
+            new_owner: Some(multisig.clone()),
+            expiry: Some(expiry),
+        }
+    );
+
+    let err = contract
+        .accept_ownership(&mut app, &sender, auction_id)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NotPendingOwner {
+            sender: sender.to_string()
+        }
+    );
+
+    app.update_block(|block| block.height += 10);
+    let err = contract
+        .accept_ownership(&mut app, &multisig, auction_id)
+        .unwrap_err();
+    assert_eq!(err, ContractError::TransferExpired);
+
+    contract
+        .transfer_ownership(&mut app, &owner, auction_id, &multisig, None)
+        .unwrap();
+    contract
+        .accept_ownership(&mut app, &multisig, auction_id)
+        .unwrap();
+
+    let resp = contract.query_pending_owner(&app, auction_id).unwrap();
+    assert_eq!(resp.new_owner, None);
+    assert_eq!(contract.query_config(&app, auction_id).unwrap().owner, multisig);
+
+    contract.bid(&mut app, &sender, auction_id, &coins(100, ATOM)).unwrap();
+
+    let err = contract.close(&mut app, &owner, auction_id).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized {
+            owner: multisig.to_string()
+        }
+    );
+
+    contract.close(&mut app, &multisig, auction_id).unwrap();
+    assert_eq!(
+        app.wrap().query_all_balances(&multisig).unwrap(),
+        coins(90, ATOM)
+    );
+}
+
+#[test]
+fn renounce_ownership() {
+    let sender = Addr::unchecked("sender");
+    let owner = Addr::unchecked("owner");
+    let keeper = Addr::unchecked("keeper");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(100, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction(&mut app, &owner, None, Decimal::percent(10), Denom::Native(ATOM.to_string()))
+        .unwrap();
+
+    let err = contract
+        .renounce_ownership(&mut app, &owner, auction_id)
+        .unwrap_err();
+    assert_eq!(err, ContractError::RenounceWithoutDeadline);
+
+    let end_height = app.block_info().height + 10;
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                end_height: Some(end_height),
+                allow_early_close: true,
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    contract
+        .renounce_ownership(&mut app, &owner, auction_id)
+        .unwrap();
+    assert!(contract.query_config(&app, auction_id).unwrap().owner_renounced);
+
+    let err = contract
+        .transfer_ownership(&mut app, &owner, auction_id, &keeper, None)
+        .unwrap_err();
+    assert_eq!(err, ContractError::OwnershipRenounced);
+
+    contract.bid(&mut app, &sender, auction_id, &coins(100, ATOM)).unwrap();
+
+    // Early close was an owner privilege
+    let err = contract.close(&mut app, &owner, auction_id).unwrap_err();
+    assert_eq!(err, ContractError::EarlyCloseErr);
+
+    app.update_block(|block| block.height = end_height);
+    contract.close(&mut app, &keeper, auction_id).unwrap();
+
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        coins(90, ATOM)
+    );
+}
+
+#[test]
+fn cancel() {
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(60, ATOM))
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(120, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let refunded = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                refund_commission_on_cancel: true,
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+    let kept = contract
+        .create_auction_with_msg(&mut app, &owner, auction_msg(&owner))
+        .unwrap();
+    let end_time = app.block_info().time.plus_seconds(100);
+    let ended = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                end_time: Some(end_time),
+                settlement_deadline: Some(end_time.plus_seconds(100)),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    for auction_id in [refunded, kept, ended] {
+        contract.bid(&mut app, &sender1, auction_id, &coins(20, ATOM)).unwrap();
+        contract.bid(&mut app, &sender2, auction_id, &coins(40, ATOM)).unwrap();
+    }
+
+    let err = contract
+        .withdraw_commission(&mut app, &owner, refunded, None, None)
+        .unwrap_err();
+    assert_eq!(err, ContractError::CommissionLocked);
+
+    let err = contract
+        .cancel(&mut app, &sender1, refunded, None)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized {
+            owner: owner.to_string()
+        }
+    );
+
+    contract
+        .cancel(&mut app, &owner, refunded, Some("item damaged"))
+        .unwrap();
+    contract.cancel(&mut app, &owner, kept, None).unwrap();
+
+    app.update_block(|block| block.time = end_time);
+    let err = contract.cancel(&mut app, &owner, ended, None).unwrap_err();
+    assert_eq!(err, ContractError::BiddingEnded);
+
+    app.update_block(|block| block.time = block.time.plus_seconds(200));
+    let err = contract.cancel(&mut app, &owner, ended, None).unwrap_err();
+    assert_eq!(err, ContractError::BiddingEnded);
+
+    let resp = contract.query_status(&app, refunded).unwrap();
+    assert_eq!(resp.status, AuctionStatus::Cancelled);
+
+    let err = contract.close(&mut app, &owner, refunded).unwrap_err();
+    assert_eq!(err, ContractError::BiddingAlreadyClosed);
+
+    for auction_id in [refunded, kept] {
+        contract.retract(&mut app, &sender1, auction_id, None).unwrap();
+        contract.retract(&mut app, &sender2, auction_id, None).unwrap();
+    }
+
+    assert_eq!(
+        contract.query_accrued_commission(&app, refunded).unwrap().amount,
+        Uint128::zero()
+    );
+    assert_eq!(
+        contract.query_accrued_commission(&app, kept).unwrap().amount,
+        Uint128::new(6)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(&sender1).unwrap(),
+        coins(38, ATOM)
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(&sender2).unwrap(),
+        coins(76, ATOM)
+    );
+}
+
+#[test]
+fn strict_funds() {
+    let sender = Addr::unchecked("sender");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &sender,
+                vec![Coin::new(100, ATOM), Coin::new(100, "btc")],
+            )
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction(&mut app, &owner, None, Decimal::percent(10), Denom::Native(ATOM.to_string()))
+        .unwrap();
+
+    let err = contract.bid(&mut app, &sender, auction_id, &[]).unwrap_err();
+    assert_eq!(err, ContractError::NoFunds);
+
+    let err = contract
+        .bid(&mut app, &sender, auction_id, &coins(10, "btc"))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidToken {
+            token: "btc".to_owned()
+        }
+    );
+
+    let err = contract
+        .bid(
+            &mut app,
+            &sender,
+            auction_id,
+            &[Coin::new(10, ATOM), Coin::new(10, "btc")],
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::MultipleCoins);
+
+    // Bank rejects sending zero coins, so go through the cw20 hook
+    let err = app
+        .execute_contract(
+            Addr::unchecked("cw20"),
+            contract.addr().clone(),
+            &ExecMsg::Receive(Cw20ReceiveMsg {
+                sender: sender.to_string(),
+                amount: Uint128::zero(),
+                msg: to_json_binary(&ReceiveMsg::Bid { auction_id }).unwrap(),
+            }),
+            &[],
+        )
+        .unwrap_err()
+        .downcast::<ContractError>()
+        .unwrap();
+    assert_eq!(err, ContractError::ZeroBid);
+
+    contract.bid(&mut app, &sender, auction_id, &coins(10, ATOM)).unwrap();
+
+    assert_eq!(
+        app.wrap().query_all_balances(contract.addr()).unwrap(),
+        coins(10, ATOM)
+    );
+}
+
+#[test]
+fn multiple_denoms() {
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &sender1,
+                vec![Coin::new(20, ATOM), Coin::new(30, OSMO)],
+            )
+            .unwrap();
+
+        router
+            .bank
+            .init_balance(storage, &sender2, coins(50, OSMO))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+    )
+    .unwrap();
+
+    let osmo_rate = vec![DenomRate {
+        denom: OSMO.to_owned(),
+        rate: Decimal::percent(50),
+    }];
+
+    let err = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                buy_now_price: Some(Uint128::new(100)),
+                denom_rates: Some(osmo_rate.clone()),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::DenomRatesUnsupported);
+
+    let err = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                denom_rates: Some(vec![DenomRate {
+                    denom: OSMO.to_owned(),
+                    rate: Decimal::zero(),
+                }]),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidDenomRate {
+            denom: OSMO.to_owned()
+        }
+    );
+
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                denom_rates: Some(osmo_rate),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    contract.bid(&mut app, &sender1, auction_id, &coins(20, ATOM)).unwrap();
+    // 45 osmo left after the commission count as 22 atom
+    contract.bid(&mut app, &sender2, auction_id, &coins(50, OSMO)).unwrap();
+
+    let resp = contract.query_highest_bid(&app, auction_id).unwrap();
+    assert_eq!(
+        resp.bid,
+        Some(Bid {
+            addr: sender2.clone(),
+            amount: Uint128::new(22)
+        })
+    );
+
+    contract
+        .set_denom_rate(&mut app, &owner, auction_id, OSMO, Some(Decimal::one()))
+        .unwrap();
+    contract.bid(&mut app, &sender1, auction_id, &coins(30, OSMO)).unwrap();
+
+    let resp = contract.query_highest_bid(&app, auction_id).unwrap();
+    assert_eq!(
+        resp.bid,
+        Some(Bid {
+            addr: sender1.clone(),
+            amount: Uint128::new(45)
+        })
+    );
+
+    contract.close(&mut app, &owner, auction_id).unwrap();
+    contract.retract(&mut app, &sender2, auction_id, None).unwrap();
+
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        vec![Coin::new(18, ATOM), Coin::new(27, OSMO)]
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(&sender2).unwrap(),
+        coins(45, OSMO)
+    );
+
+    let resp = contract.query_accrued_commission(&app, auction_id).unwrap();
+    assert_eq!(resp.amount, Uint128::new(2));
+    assert_eq!(resp.denoms, coins(8, OSMO));
+
+    contract
+        .withdraw_denom_commission(&mut app, &owner, auction_id, OSMO)
+        .unwrap();
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        vec![Coin::new(18, ATOM), Coin::new(35, OSMO)]
+    );
+    assert_eq!(
+        app.wrap().query_all_balances(contract.addr()).unwrap(),
+        coins(2, ATOM)
+    );
+}
+
+#[test]
+fn pause() {
+    let sender1 = Addr::unchecked("sender1");
+    let owner = Addr::unchecked("owner");
+    let guardian = Addr::unchecked("guardian");
+    let keeper = Addr::unchecked("keeper");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender1, coins(40, ATOM))
+            .unwrap();
+    });
+
+    let contract_id = app.store_code(bidding_platform());
+
+    let contract = BiddingPlatform::instantiate_with_msg(
+        &mut app,
+        contract_id,
+        &owner,
+        "Bidding contract",
+        InstantiateMsg {
+            guardian: Some(guardian.to_string()),
+        },
+    )
+    .unwrap();
+
+    let auction_id = contract
+        .create_auction(&mut app, &owner, None, Decimal::percent(10), Denom::Native(ATOM.to_string()))
+        .unwrap();
+
+    contract
+        .bid(&mut app, &sender1, auction_id, &coins(10, ATOM))
+        .unwrap();
+
+    let err = contract
+        .pause(&mut app, &sender1, auction_id, None)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Unauthorized {
+            owner: owner.to_string()
+        }
+    );
+
+    let err = contract.unpause(&mut app, &owner, auction_id).unwrap_err();
+    assert_eq!(err, ContractError::NotPaused);
+
+    contract
+        .pause(&mut app, &owner, auction_id, Some("bug in bidding"))
+        .unwrap();
+
+    let status = contract.query_status(&app, auction_id).unwrap();
+    assert!(status.paused);
+    assert_eq!(status.pause_reason.as_deref(), Some("bug in bidding"));
+
+    let err = contract
+        .bid(&mut app, &sender1, auction_id, &coins(20, ATOM))
+        .unwrap_err();
+    assert_eq!(err, ContractError::Paused);
+
+    let err = contract.close(&mut app, &owner, auction_id).unwrap_err();
+    assert_eq!(err, ContractError::Paused);
+
+    let err = contract
+        .renounce_ownership(&mut app, &owner, auction_id)
+        .unwrap_err();
+    assert_eq!(err, ContractError::Paused);
+
+    // Withdrawals are still possible while paused
+    contract
+        .withdraw_commission(&mut app, &owner, auction_id, None, None)
+        .unwrap();
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        coins(1, ATOM)
+    );
+
+    contract.unpause(&mut app, &guardian, auction_id).unwrap();
+
+    let status = contract.query_status(&app, auction_id).unwrap();
+    assert!(!status.paused);
+    assert_eq!(status.pause_reason, None);
+
+    contract
+        .bid(&mut app, &sender1, auction_id, &coins(20, ATOM))
+        .unwrap();
+
+    // The guardian can pause any auction as well
+    contract.pause(&mut app, &guardian, auction_id, None).unwrap();
+    contract.unpause(&mut app, &owner, auction_id).unwrap();
+
+    contract.close(&mut app, &owner, auction_id).unwrap();
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        coins(28, ATOM)
+    );
+
+    // A pause doesn't hold the bids past the settlement deadline
+    let end_time = app.block_info().time.plus_seconds(100);
+    let settlement_deadline = end_time.plus_seconds(100);
+    let auction_id = contract
+        .create_auction_with_msg(
+            &mut app,
+            &owner,
+            CreateAuctionMsg {
+                end_time: Some(end_time),
+                settlement_deadline: Some(settlement_deadline),
+                ..auction_msg(&owner)
+            },
+        )
+        .unwrap();
+
+    contract
+        .bid(&mut app, &sender1, auction_id, &coins(10, ATOM))
+        .unwrap();
+    contract.pause(&mut app, &owner, auction_id, None).unwrap();
+
+    app.update_block(|block| block.time = end_time);
+    let err = contract.close(&mut app, &keeper, auction_id).unwrap_err();
+    assert_eq!(err, ContractError::Paused);
+
+    app.update_block(|block| block.time = settlement_deadline);
+    contract.close(&mut app, &keeper, auction_id).unwrap();
+    assert_eq!(
+        app.wrap().query_all_balances(&owner).unwrap(),
+        coins(37, ATOM)
+    );
+}
@@ -1,15 +1,297 @@
-use cosmwasm_std::{Addr, Decimal, Uint128};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Binary, BlockInfo, Coin, Decimal, Timestamp, Uint128};
+use cw20::{Denom, Expiration};
+use cw_storage_plus::{
+    Index, IndexList, IndexedMap, IndexedSnapshotMap, Item, KeyDeserialize, Map, MultiIndex,
+    SnapshotMap, Strategy,
+};
 use serde::{Serialize, Deserialize};
 
+use crate::msg::{
+    AuctionMode, AuctionStatus, BidRecord, CommissionMode, DenomRate, Nft, PriceDecay, Reserve,
+    Settlement,
+};
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct State {
-    pub open: bool,
-    pub token: String,
-    pub part: Decimal
+    pub status: AuctionStatus,
+    pub token: Denom,
+    pub part: Decimal,
+    pub end_time: Option<Timestamp>,
+    pub end_height: Option<u64>,
+    pub allow_early_close: bool,
+    pub settlement_deadline: Option<Timestamp>,
+    pub keeper_reward: Decimal,
+    pub min_increment: Uint128,
+    pub min_increment_percent: Decimal,
+    pub reserve: Option<Reserve>,
+    pub buy_now_price: Option<Uint128>,
+    pub mode: AuctionMode,
+    pub start_time: Timestamp,
+    /// Deposits of sealed bids that were not revealed yet.
+    pub sealed_deposits: Uint128,
+    pub settlement: Settlement,
+    pub extension_window: u64,
+    pub extension_duration: u64,
+    pub max_end_time: Option<Timestamp>,
+    /// Besides the owner, allowed to withdraw the accrued commission.
+    pub fee_collector: Option<Addr>,
+    pub commission_mode: CommissionMode,
+    pub refund_commission_on_cancel: bool,
+    pub denom_rates: Vec<DenomRate>,
+    /// Owner gave up their privileges, but still receives the payouts.
+    pub owner_renounced: bool,
+    /// Bidding and closing are stopped while paused.
+    pub paused: bool,
+    pub pause_reason: Option<String>,
+    /// NFT held in escrow until the auction is closed.
+    pub nft: Option<Nft>,
+}
+
+impl State {
+    /// Returns true once either of the configured deadlines is reached.
+    pub fn ended(&self, block: &BlockInfo) -> bool {
+        self.end_time.is_some_and(|time| block.time >= time)
+            || self.end_height.is_some_and(|height| block.height >= height)
+    }
+
+    /// New end time after a bid placed at `time`, if the bid falls within
+    /// the extension window.
+    pub fn extended_end_time(&self, time: Timestamp) -> Option<Timestamp> {
+        let end_time = self.end_time?;
+        if self.extension_duration == 0 || time.plus_seconds(self.extension_window) < end_time {
+            return None;
+        }
+
+        let mut extended = time.plus_seconds(self.extension_duration);
+        if let Some(max_end_time) = self.max_end_time {
+            extended = extended.min(max_end_time);
+        }
+
+        (extended > end_time).then_some(extended)
+    }
+
+    /// Once the settlement deadline is reached anyone can close the auction.
+    pub fn settleable(&self, block: &BlockInfo) -> bool {
+        self.settlement_deadline
+            .is_some_and(|deadline| block.time >= deadline)
+    }
+
+    /// Lowest total a bid has to reach to outbid `highest`.
+    pub fn min_bid(&self, highest: Uint128) -> Uint128 {
+        let increment = highest * self.min_increment_percent;
+        highest + self.min_increment.max(increment)
+    }
+
+    /// Hidden reserves that were never revealed are never met.
+    pub fn reserve_met(&self, highest: Uint128) -> bool {
+        match &self.reserve {
+            None => true,
+            Some(Reserve::Public(reserve)) => highest >= *reserve,
+            Some(Reserve::Hidden(_)) => false,
+        }
+    }
+
+    /// Price the winner pays when `runner_up` is the second best bid. A
+    /// second price never drops below the public reserve.
+    pub fn clearing_price(&self, highest: Uint128, runner_up: Uint128) -> Uint128 {
+        match self.settlement {
+            Settlement::FirstPrice => highest,
+            Settlement::SecondPrice { increment } => {
+                let reserve = match self.reserve {
+                    Some(Reserve::Public(reserve)) => reserve,
+                    _ => Uint128::zero(),
+                };
+
+                (runner_up + increment.unwrap_or_default())
+                    .max(reserve)
+                    .min(highest)
+            }
+        }
+    }
+
+    /// Commission taken from an incoming bid of `amount`.
+    pub fn bid_commission(&self, amount: Uint128) -> Uint128 {
+        match self.commission_mode {
+            CommissionMode::PerBid => amount * self.part,
+            CommissionMode::OnSettlement => Uint128::zero(),
+        }
+    }
+
+    /// Commission taken from the winning `price` when the auction is sold.
+    pub fn settlement_commission(&self, price: Uint128) -> Uint128 {
+        match self.commission_mode {
+            CommissionMode::PerBid => Uint128::zero(),
+            CommissionMode::OnSettlement => price * self.part,
+        }
+    }
+
+    /// Price a dutch auction can be bought for at `time`.
+    pub fn current_price(&self, time: Timestamp) -> Option<Uint128> {
+        let AuctionMode::Dutch {
+            start_price,
+            floor_price,
+            decay,
+        } = &self.mode
+        else {
+            return None;
+        };
+
+        let elapsed = time.seconds().saturating_sub(self.start_time.seconds());
+        let range = *start_price - *floor_price;
+        let drop = match decay {
+            PriceDecay::Linear { duration } if elapsed < *duration => {
+                range.multiply_ratio(elapsed, *duration)
+            }
+            PriceDecay::Linear { .. } => range,
+            PriceDecay::Stepwise { interval, step } => {
+                step.saturating_mul(Uint128::from(elapsed / interval))
+            }
+        };
+
+        Some(start_price.saturating_sub(drop).max(*floor_price))
+    }
+
+    /// Auctions without a deadline can be closed at any time, and by the owner
+    /// before it if early close is allowed. Sealed auctions can only be closed
+    /// once the reveal phase is over.
+    pub fn closable(&self, block: &BlockInfo, by_owner: bool) -> bool {
+        if let AuctionMode::Sealed { reveal_end, .. } = self.mode {
+            return block.time >= reveal_end;
+        }
+
+        (by_owner && self.allow_early_close && !self.owner_renounced)
+            || !self.has_deadline()
+            || self.ended(block)
+    }
+
+    /// Rate of `denom` if bids in it are accepted besides the auction token.
+    pub fn rate(&self, denom: &str) -> Option<Decimal> {
+        self.denom_rates
+            .iter()
+            .find(|rate| rate.denom == denom)
+            .map(|rate| rate.rate)
+    }
+
+    /// Deposits in other denoms are only ever paid out whole - to the owner
+    /// when the bid wins, back to the bidder otherwise. Auctions that split
+    /// the winning bid or take the bids some other way can't accept them:
+    /// - cw20 tokens and non english modes take bids in `token` only,
+    /// - second price and buy now prices refund part of the winning bid,
+    /// - keeper rewards and commission refunds are paid in `token`.
+    pub fn supports_denom_rates(&self) -> bool {
+        matches!(self.token, Denom::Native(_))
+            && self.mode == AuctionMode::English
+            && self.settlement == Settlement::FirstPrice
+            && self.buy_now_price.is_none()
+            && self.keeper_reward.is_zero()
+            && !self.refund_commission_on_cancel
+    }
+
+    pub fn has_deadline(&self) -> bool {
+        self.end_time.is_some()
+            || self.end_height.is_some()
+            || matches!(self.mode, AuctionMode::Sealed { .. })
+    }
 }
 
-pub const STATE: Item<State> = Item::new("state");
-pub const OWNER: Item<Addr> = Item::new("owner");
-pub const BIDS: Map<&Addr, Uint128> = Map::new("bids");
-pub const HIGHEST_BID: Item<(Addr, Uint128)> = Item::new("highest_bid");
\ No newline at end of file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SealedBid {
+    pub commitment: Binary,
+    pub deposit: Uint128,
+}
+
+/// Part of a bid deposited in the additionally accepted denoms, along with
+/// the value it was credited in the auction token.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct DenomDeposit {
+    pub coins: Vec<Coin>,
+    pub value: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PendingOwner {
+    pub new_owner: Addr,
+    pub expiry: Option<Expiration>,
+}
+
+pub const NEXT_AUCTION_ID: Item<u64> = Item::new("next_auction_id");
+/// Allowed to pause every auction, only set if given on instantiation.
+pub const GUARDIAN: Item<Addr> = Item::new("guardian");
+pub const STATE: Map<u64, State> = Map::new("auctions");
+pub const OWNER: Map<u64, Addr> = Map::new("auction_owners");
+pub const PENDING_OWNER: Map<u64, PendingOwner> = Map::new("pending_owners");
+/// Snapshotted every block, so the winner at a past height can be looked up.
+pub const HIGHEST_BID: SnapshotMap<u64, (Addr, Uint128)> = SnapshotMap::new(
+    "highest_bids",
+    "highest_bids__checkpoints",
+    "highest_bids__changelog",
+    Strategy::EveryBlock,
+);
+pub const SECOND_BID: Map<u64, (Addr, Uint128)> = Map::new("second_bids");
+pub struct BidIndexes<'a> {
+    pub amount: MultiIndex<'a, (u64, u128), Uint128, (u64, Addr)>,
+}
+
+impl<'a> IndexList<Uint128> for BidIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Uint128>> + '_> {
+        let v: Vec<&dyn Index<Uint128>> = vec![&self.amount];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Bid totals of every bidder, indexed by amount for the leaderboard and
+/// snapshotted every block.
+pub fn bid_totals<'a>() -> IndexedSnapshotMap<'a, (u64, &'a Addr), Uint128, BidIndexes<'a>> {
+    let indexes = BidIndexes {
+        amount: MultiIndex::new(
+            |pk, amount| {
+                let (auction_id, _) = <(u64, Addr)>::from_slice(pk).unwrap();
+                (auction_id, amount.u128())
+            },
+            "auction_bids",
+            "auction_bids__amount",
+        ),
+    };
+    IndexedSnapshotMap::new(
+        "auction_bids",
+        "auction_bids__checkpoints",
+        "auction_bids__changelog",
+        Strategy::EveryBlock,
+        indexes,
+    )
+}
+
+pub struct BidHistoryIndexes<'a> {
+    pub bidder: MultiIndex<'a, (u64, Addr), BidRecord, (u64, u64)>,
+}
+
+impl<'a> IndexList<BidRecord> for BidHistoryIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<BidRecord>> + '_> {
+        let v: Vec<&dyn Index<BidRecord>> = vec![&self.bidder];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn bid_records<'a>() -> IndexedMap<'a, (u64, u64), BidRecord, BidHistoryIndexes<'a>> {
+    let indexes = BidHistoryIndexes {
+        bidder: MultiIndex::new(
+            |_pk, bid| (bid.auction_id, bid.bidder.clone()),
+            "bid_history",
+            "bid_history__bidder",
+        ),
+    };
+    IndexedMap::new("bid_history", indexes)
+}
+
+/// Id the next bid of an auction is recorded in the history under.
+pub const NEXT_BID_ID: Map<u64, u64> = Map::new("next_bid_id");
+
+/// Commission collected from bids, waiting to be withdrawn.
+pub const ACCRUED_COMMISSION: Map<u64, Uint128> = Map::new("accrued_commission");
+pub const DENOM_DEPOSITS: Map<(u64, &Addr), DenomDeposit> = Map::new("denom_deposits");
+pub const ACCRUED_DENOM_COMMISSION: Map<(u64, &str), Uint128> =
+    Map::new("accrued_denom_commission");
+/// Commission paid by each bidder, only kept when it is refunded on cancel or
+/// pays the keeper reward.
+pub const BID_COMMISSIONS: Map<(u64, &Addr), Uint128> = Map::new("bid_commissions");
+pub const SEALED_BIDS: Map<(u64, &Addr), SealedBid> = Map::new("sealed_bids");